@@ -0,0 +1,310 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::time::Duration;
+
+use sudoku_solver::solver::backend::Engine;
+
+pub type ArgumentsResult = Result<Arguments, ArgumentsError>;
+pub type ArgumentsError = Cow<'static, str>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Solve,
+    Generate,
+    Rate,
+    Hint,
+    Validate,
+    Convert,
+    Play,
+    Batch,
+    Dedupe,
+}
+
+impl Command {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "solve" => Some(Command::Solve),
+            "generate" => Some(Command::Generate),
+            "rate" => Some(Command::Rate),
+            "hint" => Some(Command::Hint),
+            "validate" => Some(Command::Validate),
+            "convert" => Some(Command::Convert),
+            "play" => Some(Command::Play),
+            "batch" => Some(Command::Batch),
+            "dedupe" => Some(Command::Dedupe),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Grid,
+    Line,
+    Pretty,
+    Outline,
+    Svg,
+    Dimacs,
+    Samurai,
+}
+
+impl Format {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "grid" => Some(Format::Grid),
+            "line" => Some(Format::Line),
+            "pretty" => Some(Format::Pretty),
+            "outline" => Some(Format::Outline),
+            "svg" => Some(Format::Svg),
+            "dimacs" => Some(Format::Dimacs),
+            "samurai" => Some(Format::Samurai),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Arguments {
+    pub command: Option<Command>,
+    pub input: Option<String>,
+    pub input_format: Format,
+    pub output_format: Format,
+    pub seed: Option<u64>,
+    pub timeout: Option<Duration>,
+    pub allow_guessing: bool,
+    pub engine: Engine,
+    pub threads: Option<usize>,
+    pub stats: bool,
+    pub verbosity: Verbosity,
+    pub help: bool,
+}
+
+impl Default for Arguments {
+    fn default() -> Self {
+        Self {
+            command: None,
+            input: None,
+            input_format: Format::Grid,
+            output_format: Format::Pretty,
+            seed: None,
+            timeout: None,
+            allow_guessing: true,
+            engine: Engine::Propagation,
+            threads: None,
+            stats: false,
+            verbosity: Verbosity::Normal,
+            help: false,
+        }
+    }
+}
+
+impl Arguments {
+    pub fn parse(arguments: Vec<String>) -> ArgumentsResult {
+        let mut result = Self::default();
+        let mut arguments = arguments.into_iter();
+        while let Some(argument) = arguments.next() {
+            match argument.as_str() {
+                "-h" | "--help" => result.help = true,
+                "-v" | "--verbose" => result.verbosity = Verbosity::Verbose,
+                "-q" | "--quiet" => result.verbosity = Verbosity::Quiet,
+                "-i" | "--input" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    result.input = Some(value);
+                }
+                "-f" | "--from" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    result.input_format = Self::parse_format(&value)?;
+                    if matches!(
+                        result.input_format,
+                        Format::Pretty | Format::Outline | Format::Svg
+                    ) {
+                        let message = format!("The {} format can only be used for output", value);
+                        return Err(message.into());
+                    }
+                }
+                "-t" | "--to" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    result.output_format = Self::parse_format(&value)?;
+                }
+                "-s" | "--seed" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("Invalid seed: '{}'", value))?;
+                    result.seed = Some(seed);
+                }
+                "--no-guessing" => result.allow_guessing = false,
+                "--stats" => result.stats = true,
+                "-e" | "--engine" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    result.engine = Engine::from_name(&value)
+                        .ok_or_else(|| format!("Unknown engine: '{}'", value))?;
+                }
+                "-j" | "--threads" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    let threads = value
+                        .parse()
+                        .ok()
+                        .filter(|threads| *threads > 0)
+                        .ok_or_else(|| format!("Invalid number of threads: '{}'", value))?;
+                    result.threads = Some(threads);
+                }
+                "--timeout" => {
+                    let value = Self::next_value(&mut arguments, &argument)?;
+                    result.timeout = Some(Self::parse_timeout(&value)?);
+                }
+                _ if argument.starts_with('-') && argument != "-" => {
+                    return Err(format!("Unknown option: '{}'", argument).into());
+                }
+                _ if result.command.is_none() => {
+                    let command = Command::from_name(&argument)
+                        .ok_or_else(|| format!("Unknown command: '{}'", argument))?;
+                    result.command = Some(command);
+                }
+                _ if result.input.is_none() => result.input = Some(argument),
+                _ => return Err(format!("Unexpected argument: '{}'", argument).into()),
+            }
+        }
+        let command = result.command.unwrap_or(Command::Solve);
+        if result.threads.is_some()
+            && command != Command::Batch
+            && result.engine != Engine::Backtracking
+        {
+            return Err("Only the backtracking engine searches a grid with several threads".into());
+        }
+        if result.stats && result.engine != Engine::Propagation {
+            return Err("Statistics are only available for the propagation engine".into());
+        }
+        if result.input_format == Format::Samurai {
+            if !matches!(
+                command,
+                Command::Solve | Command::Validate | Command::Convert
+            ) {
+                return Err("Samurai grids can only be solved, validated and converted".into());
+            }
+            if result.engine != Engine::Propagation || result.stats {
+                return Err("Samurai grids are only solved by the propagation engine".into());
+            }
+        }
+        Ok(result)
+    }
+
+    fn next_value(
+        arguments: &mut impl Iterator<Item = String>,
+        option: &str,
+    ) -> Result<String, ArgumentsError> {
+        arguments
+            .next()
+            .ok_or_else(|| format!("Missing value for option '{}'", option).into())
+    }
+
+    fn parse_format(value: &str) -> Result<Format, ArgumentsError> {
+        Format::from_name(value).ok_or_else(|| format!("Unknown format: '{}'", value).into())
+    }
+
+    fn parse_timeout(value: &str) -> Result<Duration, ArgumentsError> {
+        let seconds = value
+            .parse::<f64>()
+            .ok()
+            .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+            .ok_or_else(|| format!("Invalid timeout: '{}'", value))?;
+        Ok(Duration::from_secs_f64(seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[&str]) -> ArgumentsResult {
+        let arguments = arguments
+            .iter()
+            .map(|argument| argument.to_string())
+            .collect();
+        Arguments::parse(arguments)
+    }
+
+    #[test]
+    fn test_command_with_options_is_parsed() -> Result<(), ArgumentsError> {
+        let actual = parse(&[
+            "solve",
+            "-f",
+            "line",
+            "--to",
+            "grid",
+            "--timeout",
+            "2.5",
+            "-v",
+        ])?;
+        let expected = Arguments {
+            command: Some(Command::Solve),
+            input_format: Format::Line,
+            output_format: Format::Grid,
+            timeout: Some(Duration::from_millis(2500)),
+            verbosity: Verbosity::Verbose,
+            ..Arguments::default()
+        };
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_positional_input_is_parsed() -> Result<(), ArgumentsError> {
+        let actual = parse(&["validate", "puzzle.grid"])?;
+        assert_eq!(Some(Command::Validate), actual.command);
+        assert_eq!(Some("puzzle.grid".to_owned()), actual.input);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        assert!(parse(&["unknown"]).is_err());
+        assert!(parse(&["solve", "--bogus"]).is_err());
+        assert!(parse(&["generate", "--seed"]).is_err());
+        assert!(parse(&["generate", "--seed", "abc"]).is_err());
+        assert!(parse(&["convert", "--from", "pretty"]).is_err());
+        assert!(parse(&["convert", "--from", "outline"]).is_err());
+        assert!(parse(&["convert", "--from", "svg"]).is_err());
+        assert!(parse(&["solve", "--engine", "quantum"]).is_err());
+        assert!(parse(&["batch", "--threads", "0"]).is_err());
+        assert!(parse(&["solve", "-j", "4"]).is_err());
+        assert!(parse(&["solve", "-j", "4", "--engine", "sat"]).is_err());
+        assert!(parse(&["solve", "-j", "4", "--engine", "backtracking"]).is_ok());
+        assert!(parse(&["batch", "-j", "4", "--engine", "sat"]).is_ok());
+        assert!(parse(&["solve", "--stats", "--engine", "sat"]).is_err());
+        assert!(parse(&["solve", "a.grid", "b.grid"]).is_err());
+        assert!(parse(&["rate", "--from", "samurai"]).is_err());
+        assert!(parse(&["dedupe", "--from", "samurai"]).is_err());
+        assert!(parse(&["--from", "samurai", "--engine", "sat"]).is_err());
+    }
+}
@@ -0,0 +1,696 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::io::Read;
+use std::time::Instant;
+
+use sudoku_solver::grid::digit::Digit;
+use sudoku_solver::grid::generator::Generator;
+use sudoku_solver::grid::parser::Parser;
+use sudoku_solver::grid::renderer::Renderer;
+use sudoku_solver::grid::samurai::Samurai;
+use sudoku_solver::grid::svg::SvgRenderer;
+use sudoku_solver::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+use sudoku_solver::solver::backend::SearchLimits;
+use sudoku_solver::solver::batch::BatchSolver;
+use sudoku_solver::solver::config::{SolverConfig, Strategy};
+use sudoku_solver::solver::samurai::SamuraiSolver;
+use sudoku_solver::solver::sat::cnf::{decode_model, parse_model, Cnf};
+use sudoku_solver::solver::{Solver, SolverError};
+
+use crate::cli::arguments::{Arguments, Command, Format, Verbosity};
+use crate::cli::play::Play;
+use crate::cli::puzzle::PuzzleGenerator;
+use crate::cli::random::Random;
+
+mod arguments;
+mod play;
+mod puzzle;
+mod random;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Failure = 1,
+    Usage = 2,
+    InvalidGrid = 3,
+    Unsolvable = 4,
+    Ambiguous = 5,
+    LimitExceeded = 6,
+    GuessingRequired = 7,
+}
+
+// Grids solved by deduction are rated by the first of these sets of strategies that solves them.
+const RATINGS: [(&str, &[Strategy]); 3] = [
+    ("easy", &[Strategy::NakedSingles, Strategy::HiddenSingles]),
+    (
+        "medium",
+        &[
+            Strategy::NakedSingles,
+            Strategy::HiddenSingles,
+            Strategy::LockedCandidates,
+            Strategy::CageCombinations,
+            Strategy::AdjacentPairs,
+            Strategy::CellConstraints,
+        ],
+    ),
+    ("hard", &Strategy::ALL),
+];
+
+struct CommandError {
+    code: ExitCode,
+    message: Cow<'static, str>,
+}
+
+impl CommandError {
+    fn new<M: Into<Cow<'static, str>>>(code: ExitCode, message: M) -> Self {
+        let message = message.into();
+        Self { code, message }
+    }
+}
+
+type CommandResult = Result<(), CommandError>;
+
+pub fn run(arguments: Vec<String>) -> ExitCode {
+    let arguments = match Arguments::parse(arguments) {
+        Ok(arguments) => arguments,
+        Err(error) => {
+            eprintln!("{}", error);
+            eprintln!("Try '--help' for more information.");
+            return ExitCode::Usage;
+        }
+    };
+    if arguments.help {
+        print!("{}", help(arguments.command));
+        return ExitCode::Success;
+    }
+    let command = arguments.command.unwrap_or(Command::Solve);
+    let result = match command {
+        Command::Solve => solve(&arguments),
+        Command::Generate => generate(&arguments),
+        Command::Rate => rate(&arguments),
+        Command::Hint => hint(&arguments),
+        Command::Validate => validate(&arguments),
+        Command::Convert => convert(&arguments),
+        Command::Play => play(&arguments),
+        Command::Batch => batch(&arguments),
+        Command::Dedupe => dedupe(&arguments),
+    };
+    match result {
+        Ok(()) => ExitCode::Success,
+        Err(error) => {
+            if arguments.verbosity > Verbosity::Quiet {
+                eprintln!("{}", error.message);
+            }
+            error.code
+        }
+    }
+}
+
+fn solve(arguments: &Arguments) -> CommandResult {
+    if arguments.input_format == Format::Samurai {
+        return solve_samurai(arguments);
+    }
+    let grid = read_valid_grid(arguments)?;
+    let start = Instant::now();
+    let solved_grid = solve_grid(grid, arguments)?;
+    if !solved_grid.is_solved_correctly() {
+        return Err(CommandError::new(
+            ExitCode::Failure,
+            "Grid has been solved incorrectly!",
+        ));
+    }
+    print_grid(&solved_grid, arguments.output_format)?;
+    if arguments.verbosity == Verbosity::Verbose {
+        eprintln!("Grid has been solved correctly in {:?}.", start.elapsed());
+    }
+    Ok(())
+}
+
+fn generate(arguments: &Arguments) -> CommandResult {
+    let seed = arguments.seed.unwrap_or_else(Random::seed_from_time);
+    let mut generator = PuzzleGenerator::new(seed);
+    let puzzle = generator.generate();
+    print_grid(&puzzle, arguments.output_format)?;
+    if arguments.verbosity == Verbosity::Verbose {
+        eprintln!("Seed: {}", seed);
+        eprintln!("Givens: {}", count_known_digits(&puzzle));
+    }
+    Ok(())
+}
+
+fn rate(arguments: &Arguments) -> CommandResult {
+    let grid = read_valid_grid(arguments)?;
+    for (rating, strategies) in &RATINGS {
+        let config = SolverConfig {
+            max_duration: arguments.timeout,
+            strategies: strategies.to_vec(),
+            ..SolverConfig::deduction_only()
+        };
+        match Solver::new(grid.clone(), config).solve() {
+            Ok(_) => {
+                println!("{}", rating);
+                return Ok(());
+            }
+            Err(SolverError::GuessingRequired) => {}
+            Err(error) => {
+                return Err(CommandError::new(
+                    solver_error_code(error),
+                    error.to_string(),
+                ))
+            }
+        }
+    }
+    solve_grid(grid, arguments)?;
+    println!("extreme");
+    Ok(())
+}
+
+fn hint(arguments: &Arguments) -> CommandResult {
+    let grid = read_valid_grid(arguments)?;
+    let solved_grid = solve_grid(grid.clone(), arguments)?;
+    match find_hint(&grid, &solved_grid) {
+        Some((x, y, value)) => println!("r{}c{} = {}", y + 1, x + 1, value),
+        None => println!("Grid is already solved."),
+    }
+    Ok(())
+}
+
+fn play(arguments: &Arguments) -> CommandResult {
+    let grid = read_valid_grid(arguments)?;
+    let play = Play::new(grid);
+    play.run()
+        .map_err(|error| CommandError::new(ExitCode::Failure, error.to_string()))
+}
+
+fn batch(arguments: &Arguments) -> CommandResult {
+    let grids = read_puzzles(arguments)?;
+    let config = SolverConfig {
+        allow_guessing: arguments.allow_guessing,
+        ..SolverConfig::default()
+    };
+    let mut solver = BatchSolver::new(arguments.engine.create(config));
+    solver.set_limits(SearchLimits {
+        max_duration: arguments.timeout,
+        ..SearchLimits::default()
+    });
+    if let Some(threads) = arguments.threads {
+        solver.set_threads(threads);
+    }
+    let start = Instant::now();
+    let count = grids.len();
+    let mut failure = None;
+    let mut failures = 0;
+    for result in solver.solve(grids) {
+        let number = result.index + 1;
+        match result.result {
+            Ok(solved_grid) => {
+                print_grid(&solved_grid, arguments.output_format)?;
+                if arguments.verbosity == Verbosity::Verbose {
+                    eprintln!("Puzzle {} solved in {:?}.", number, result.elapsed);
+                }
+            }
+            Err(error) => {
+                if arguments.verbosity > Verbosity::Quiet {
+                    eprintln!("Puzzle {}: {}", number, error);
+                }
+                failure.get_or_insert(error);
+                failures += 1;
+            }
+        }
+    }
+    if arguments.verbosity == Verbosity::Verbose {
+        eprintln!(
+            "Solved {} of {} puzzles in {:?} using {} threads.",
+            count - failures,
+            count,
+            start.elapsed(),
+            solver.threads()
+        );
+    }
+    match failure {
+        Some(error) => Err(CommandError::new(
+            solver_error_code(error),
+            format!("{} of {} puzzles could not be solved", failures, count),
+        )),
+        None => Ok(()),
+    }
+}
+
+// Equivalent puzzles share their canonical form, so only the first puzzle with each form is kept.
+fn dedupe(arguments: &Arguments) -> CommandResult {
+    let grids = read_puzzles(arguments)?;
+    let count = grids.len();
+    let mut canonical_forms = BTreeMap::new();
+    for (index, grid) in grids.into_iter().enumerate() {
+        let number = index + 1;
+        match canonical_forms.entry(grid.get_canonical_form()) {
+            Entry::Occupied(entry) => {
+                if arguments.verbosity == Verbosity::Verbose {
+                    eprintln!("Puzzle {} is equivalent to puzzle {}.", number, entry.get());
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(number);
+                print_grid(&grid, arguments.output_format)?;
+            }
+        }
+    }
+    if arguments.verbosity == Verbosity::Verbose {
+        eprintln!("Kept {} of {} puzzles.", canonical_forms.len(), count);
+    }
+    Ok(())
+}
+
+fn validate(arguments: &Arguments) -> CommandResult {
+    if arguments.input_format == Format::Samurai {
+        read_valid_samurai(arguments)?;
+    } else {
+        read_valid_grid(arguments)?;
+    }
+    if arguments.verbosity > Verbosity::Quiet {
+        println!("Grid is valid.");
+    }
+    Ok(())
+}
+
+fn convert(arguments: &Arguments) -> CommandResult {
+    if arguments.input_format == Format::Samurai {
+        let samurai = read_samurai(arguments)?;
+        return print_samurai(&samurai, arguments.output_format);
+    }
+    let grid = read_grid(arguments)?;
+    print_grid(&grid, arguments.output_format)
+}
+
+// Puzzles are given one per line, skipping empty lines and comments.
+fn read_puzzles(arguments: &Arguments) -> Result<Vec<Grid>, CommandError> {
+    let syntax = read_input(arguments.input.as_deref())
+        .map_err(|error| CommandError::new(ExitCode::Failure, error.to_string()))?;
+    let mut puzzles: Vec<(usize, String)> = Vec::new();
+    for (number, line) in syntax.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Declaration lines of variants belong to the puzzle above them.
+        match puzzles.last_mut() {
+            Some((_, puzzle)) if line.starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                puzzle.push('\n');
+                puzzle.push_str(line);
+            }
+            _ => puzzles.push((number + 1, line.to_owned())),
+        }
+    }
+    let parser = Parser::new();
+    let mut grids = Vec::with_capacity(puzzles.len());
+    for (number, puzzle) in puzzles {
+        let grid = parser.parse_line(puzzle).map_err(|error| {
+            CommandError::new(ExitCode::InvalidGrid, format!("Line {}: {}", number, error))
+        })?;
+        grids.push(grid);
+    }
+    Ok(grids)
+}
+
+fn read_grid(arguments: &Arguments) -> Result<Grid, CommandError> {
+    let syntax = read_input(arguments.input.as_deref())
+        .map_err(|error| CommandError::new(ExitCode::Failure, error.to_string()))?;
+    let parser = Parser::new();
+    let grid = match arguments.input_format {
+        Format::Line => parser.parse_line(syntax),
+        Format::Dimacs => parse_model(&syntax).and_then(|model| decode_model(&model)),
+        _ => parser.parse(syntax),
+    };
+    grid.map_err(|error| CommandError::new(ExitCode::InvalidGrid, error))
+}
+
+fn read_valid_grid(arguments: &Arguments) -> Result<Grid, CommandError> {
+    let grid = read_grid(arguments)?;
+    let report = grid.validate();
+    if report.is_valid() {
+        Ok(grid)
+    } else {
+        let message = format!("Grid is invalid:\n{}", report.to_string().trim_end());
+        Err(CommandError::new(ExitCode::InvalidGrid, message))
+    }
+}
+
+fn read_samurai(arguments: &Arguments) -> Result<Samurai, CommandError> {
+    let syntax = read_input(arguments.input.as_deref())
+        .map_err(|error| CommandError::new(ExitCode::Failure, error.to_string()))?;
+    Parser::new()
+        .parse_samurai(syntax)
+        .map_err(|error| CommandError::new(ExitCode::InvalidGrid, error))
+}
+
+fn read_valid_samurai(arguments: &Arguments) -> Result<Samurai, CommandError> {
+    let samurai = read_samurai(arguments)?;
+    let mut message = String::new();
+    for (index, report) in samurai.validate().iter().enumerate() {
+        if !report.is_valid() {
+            let name = Samurai::get_grid_name(index);
+            message.push_str(&format!("\nThe {} grid is invalid:\n{}", name, report));
+        }
+    }
+    if message.is_empty() {
+        Ok(samurai)
+    } else {
+        let message = format!("Samurai is invalid:{}", message.trim_end());
+        Err(CommandError::new(ExitCode::InvalidGrid, message))
+    }
+}
+
+fn read_input(path: Option<&str>) -> io::Result<String> {
+    match path {
+        Some(path) if path != "-" => fs::read_to_string(path),
+        _ => {
+            let mut result = String::new();
+            io::stdin().read_to_string(&mut result)?;
+            Ok(result)
+        }
+    }
+}
+
+fn solve_grid(grid: Grid, arguments: &Arguments) -> Result<Grid, CommandError> {
+    let config = SolverConfig {
+        allow_guessing: arguments.allow_guessing,
+        threads: arguments.threads.unwrap_or(1),
+        ..SolverConfig::default()
+    };
+    let result = if arguments.stats {
+        let config = SolverConfig {
+            max_duration: arguments.timeout,
+            ..config
+        };
+        let solver = Solver::new(grid, config);
+        let (result, stats) = solver.find_solutions_with_stats(2);
+        eprint!("{}", stats);
+        result
+    } else {
+        let solver = arguments.engine.create(config);
+        let limits = SearchLimits {
+            max_duration: arguments.timeout,
+            ..SearchLimits::default()
+        };
+        solver.find_solutions_with_limits(grid, 2, &limits)
+    };
+    let mut solutions =
+        result.map_err(|error| CommandError::new(solver_error_code(error), error.to_string()))?;
+    match solutions.len() {
+        0 => Err(CommandError::new(
+            ExitCode::Unsolvable,
+            SolverError::NoSolution.to_string(),
+        )),
+        1 => Ok(solutions.remove(0)),
+        _ => Err(CommandError::new(
+            ExitCode::Ambiguous,
+            "Grid has more than one solution",
+        )),
+    }
+}
+
+fn solve_samurai(arguments: &Arguments) -> CommandResult {
+    let samurai = read_valid_samurai(arguments)?;
+    let start = Instant::now();
+    let config = SolverConfig {
+        max_duration: arguments.timeout,
+        allow_guessing: arguments.allow_guessing,
+        ..SolverConfig::default()
+    };
+    let mut solutions = SamuraiSolver::new(config)
+        .find_solutions(samurai, 2)
+        .map_err(|error| CommandError::new(solver_error_code(error), error.to_string()))?;
+    let solved_samurai = match solutions.len() {
+        0 => Err(CommandError::new(
+            ExitCode::Unsolvable,
+            SolverError::NoSolution.to_string(),
+        )),
+        1 => Ok(solutions.remove(0)),
+        _ => Err(CommandError::new(
+            ExitCode::Ambiguous,
+            "Grid has more than one solution",
+        )),
+    }?;
+    if !solved_samurai.is_solved_correctly() {
+        return Err(CommandError::new(
+            ExitCode::Failure,
+            "Samurai has been solved incorrectly!",
+        ));
+    }
+    print_samurai(&solved_samurai, arguments.output_format)?;
+    if arguments.verbosity == Verbosity::Verbose {
+        eprintln!(
+            "Samurai has been solved correctly in {:?}.",
+            start.elapsed()
+        );
+    }
+    Ok(())
+}
+
+fn solver_error_code(error: SolverError) -> ExitCode {
+    match error {
+        SolverError::NoSolution => ExitCode::Unsolvable,
+        SolverError::GuessingRequired => ExitCode::GuessingRequired,
+        SolverError::LimitExceeded(_) => ExitCode::LimitExceeded,
+        SolverError::Cancelled => ExitCode::Failure,
+    }
+}
+
+fn print_grid(grid: &Grid, format: Format) -> CommandResult {
+    let generator = Generator::new();
+    let output = match format {
+        Format::Grid => generator.generate_syntax(grid),
+        Format::Line => generator.generate_line(grid),
+        Format::Pretty => generator.generate(grid),
+        Format::Outline => Renderer::new().render(grid),
+        Format::Svg => SvgRenderer::new().render(grid),
+        // The formula only approximates constraints on more than two cells, which the SAT engine
+        // completes while solving. Parity markers and inequalities are encoded exactly.
+        Format::Dimacs
+            if grid
+                .constraints
+                .get_cell_constraints()
+                .any(|constraint| constraint.get_cells().len() > 2) =>
+        {
+            Err("Thermometers, arrows and sandwiches cannot be written as dimacs".into())
+        }
+        Format::Dimacs => Ok(Cnf::from_grid(grid).to_dimacs()),
+        Format::Samurai => Err("The samurai format can only be used for Samurai grids".into()),
+    };
+    let output = output.map_err(|error| CommandError::new(ExitCode::Failure, error))?;
+    println!("{}", output.trim_end());
+    Ok(())
+}
+
+fn print_samurai(samurai: &Samurai, format: Format) -> CommandResult {
+    let output = match format {
+        Format::Pretty | Format::Outline => Renderer::new().render_samurai(samurai),
+        Format::Samurai => Generator::new().generate_samurai(samurai),
+        _ => {
+            Err("Samurai grids can only be written in the pretty, outline or samurai format".into())
+        }
+    };
+    let output = output.map_err(|error| CommandError::new(ExitCode::Failure, error))?;
+    println!("{}", output.trim_end());
+    Ok(())
+}
+
+fn count_known_digits(grid: &Grid) -> usize {
+    (0..GRID_JOINT_SIZE)
+        .flat_map(|y| (0..GRID_JOINT_SIZE).map(move |x| (x, y)))
+        .filter(|(x, y)| matches!(grid.get_digit(*x, *y), Digit::Known(_)))
+        .count()
+}
+
+fn find_hint(grid: &Grid, solved_grid: &Grid) -> Option<(usize, usize, u32)> {
+    let mut best_hint = None;
+    let units = grid.get_units();
+    for y in 0..GRID_JOINT_SIZE {
+        for x in 0..GRID_JOINT_SIZE {
+            if let (Digit::Unknown(_), Digit::Known(value)) =
+                (grid.get_digit(x, y), solved_grid.get_digit(x, y))
+            {
+                let neighbours = grid.get_unit_neighbour_digits(&units, x, y);
+                let candidates = (1..=SUBGRID_LENGTH as u32)
+                    .filter(|value| !neighbours.contains(&&Digit::Known(*value)))
+                    .count();
+                match best_hint {
+                    Some((best_candidates, _)) if best_candidates <= candidates => {}
+                    _ => best_hint = Some((candidates, (x, y, *value))),
+                }
+            }
+        }
+    }
+    best_hint.map(|(_, hint)| hint)
+}
+
+fn help(command: Option<Command>) -> String {
+    let (usage, description, options) = match command {
+        None => {
+            return format!(
+                "Usage: sudoku-solver [COMMAND] [OPTIONS] [INPUT]\n\n\
+                 Commands:\n\
+                 \x20 solve     Solve a grid and print the solution (default)\n\
+                 \x20 generate  Generate a new puzzle with a unique solution\n\
+                 \x20 rate      Rate the difficulty of a grid\n\
+                 \x20 hint      Show the value of the easiest empty cell\n\
+                 \x20 validate  Check a grid for conflicts\n\
+                 \x20 convert   Convert a grid between formats\n\
+                 \x20 play      Play a grid interactively in the terminal\n\
+                 \x20 batch     Solve many puzzles, one per line, in parallel\n\
+                 \x20 dedupe    Remove puzzles equivalent to an earlier one from a list\n\n\
+                 Options:\n{}{}{}{}{}{}\n{}",
+                INPUT_OPTIONS,
+                OUTPUT_OPTIONS,
+                SOLVER_OPTIONS,
+                THREAD_OPTIONS,
+                GENERATOR_OPTIONS,
+                COMMON_OPTIONS,
+                EXIT_CODES
+            );
+        }
+        Some(Command::Solve) => (
+            "solve",
+            "Solve a grid and print the solution.",
+            vec![
+                INPUT_OPTIONS,
+                OUTPUT_OPTIONS,
+                SOLVER_OPTIONS,
+                THREAD_OPTIONS,
+            ],
+        ),
+        Some(Command::Generate) => (
+            "generate",
+            "Generate a new puzzle with a unique solution.",
+            vec![OUTPUT_OPTIONS, GENERATOR_OPTIONS],
+        ),
+        Some(Command::Rate) => (
+            "rate",
+            "Rate the difficulty of a grid by the hardest strategies needed to solve it: easy for\n\
+             naked and hidden singles, medium for locked candidates and the variant constraints,\n\
+             hard for naked and hidden subsets and extreme when guessing is needed.",
+            vec![INPUT_OPTIONS, SOLVER_OPTIONS],
+        ),
+        Some(Command::Hint) => (
+            "hint",
+            "Show the value of the empty cell with the fewest possible values.",
+            vec![INPUT_OPTIONS, SOLVER_OPTIONS],
+        ),
+        Some(Command::Validate) => (
+            "validate",
+            "Check a grid for repeated digits and cells without possible values.",
+            vec![INPUT_OPTIONS],
+        ),
+        Some(Command::Convert) => (
+            "convert",
+            "Convert a grid between formats.",
+            vec![INPUT_OPTIONS, OUTPUT_OPTIONS],
+        ),
+        Some(Command::Play) => (
+            "play",
+            "Play a grid interactively in the terminal. Keys are listed below the grid.",
+            vec![INPUT_OPTIONS],
+        ),
+        Some(Command::Batch) => (
+            "batch",
+            "Solve puzzles given one per line in the line format, using all CPU cores.\n\
+             Empty lines and lines starting with '#' are skipped, declarations of variants\n\
+             follow their puzzle on separate lines. Solutions are printed in input order\n\
+             and failures are reported on standard error.",
+            vec![INPUT_OPTIONS, OUTPUT_OPTIONS, SOLVER_OPTIONS, BATCH_OPTIONS],
+        ),
+        Some(Command::Dedupe) => (
+            "dedupe",
+            "Print the puzzles given one per line in the line format, skipping those equivalent\n\
+             to an earlier puzzle by rotating, reflecting or transposing the grid, swapping\n\
+             bands, stacks, or rows and columns within them, and relabelling the digits.\n\
+             Puzzles with variant constraints are only skipped when repeated exactly.",
+            vec![INPUT_OPTIONS, OUTPUT_OPTIONS],
+        ),
+    };
+    format!(
+        "Usage: sudoku-solver {} [OPTIONS] [INPUT]\n\n{}\n\nOptions:\n{}{}\n{}",
+        usage,
+        description,
+        options.concat(),
+        COMMON_OPTIONS,
+        EXIT_CODES
+    )
+}
+
+const INPUT_OPTIONS: &str = "\
+\x20 -i, --input <FILE>    Read the grid from FILE instead of standard input
+\x20 -f, --from <FORMAT>   Input format: grid (default), line, dimacs (a SAT model) or
+\x20                       samurai (five overlapping grids as a 21x21 layout)
+";
+
+const OUTPUT_OPTIONS: &str = "\
+\x20 -t, --to <FORMAT>     Output format: pretty (default), grid, line, outline or svg
+\x20                       (with variant constraints drawn), dimacs (CNF) or samurai
+";
+
+const SOLVER_OPTIONS: &str = "\
+\x20     --timeout <SECS>  Give up solving after SECS seconds
+\x20     --no-guessing     Fail instead of guessing when no deduction is possible
+\x20 -e, --engine <NAME>   Solving engine: propagation (default), backtracking,
+\x20                       dancing-links or sat
+\x20     --stats           Print solver statistics to standard error (propagation engine only)
+";
+
+const BATCH_OPTIONS: &str = "\
+\x20 -j, --threads <N>     Number of puzzles solved at once (default: number of CPU cores)
+";
+
+const THREAD_OPTIONS: &str = "\
+\x20 -j, --threads <N>     Number of threads searching a single grid with the backtracking
+\x20                       engine (default: 1)
+";
+
+const GENERATOR_OPTIONS: &str = "\
+\x20 -s, --seed <NUMBER>   Seed used to generate the puzzle
+";
+
+const COMMON_OPTIONS: &str = "\
+\x20 -v, --verbose         Print additional information
+\x20 -q, --quiet           Do not print error messages
+\x20 -h, --help            Print help
+";
+
+const EXIT_CODES: &str = "\
+Exit codes:
+\x20 0  Success
+\x20 1  Input or output error, or an incorrect solution
+\x20 2  Invalid command line
+\x20 3  Invalid grid
+\x20 4  Grid has no solution
+\x20 5  Grid has more than one solution
+\x20 6  Solver limit exceeded
+\x20 7  Grid cannot be solved without guessing
+";
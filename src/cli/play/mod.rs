@@ -0,0 +1,243 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::io;
+
+use sudoku_solver::game::{Game, GameResult};
+use sudoku_solver::grid::digit::Digit;
+use sudoku_solver::grid::{Grid, GRID_JOINT_SIZE};
+
+use crate::cli::find_hint;
+use crate::cli::play::terminal::{Key, Terminal};
+
+mod render;
+mod terminal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Continue,
+    Quit,
+}
+
+pub struct Play {
+    game: Game,
+    cursor_x: usize,
+    cursor_y: usize,
+    pencil_mode: bool,
+    checked: bool,
+    message: String,
+}
+
+impl Play {
+    pub fn new(grid: Grid) -> Self {
+        Self {
+            game: Game::new(grid),
+            cursor_x: 0,
+            cursor_y: 0,
+            pencil_mode: false,
+            checked: false,
+            message: String::new(),
+        }
+    }
+
+    pub fn run(mut self) -> io::Result<()> {
+        let mut terminal = Terminal::open()?;
+        loop {
+            terminal.draw(&self.render())?;
+            let key = terminal.read_key()?;
+            if self.handle_key(key) == Action::Quit {
+                return Ok(());
+            }
+        }
+    }
+
+    fn handle_key(&mut self, key: Key) -> Action {
+        self.message.clear();
+        let (x, y) = (self.cursor_x, self.cursor_y);
+        match key {
+            Key::Up | Key::Char('k') => self.move_cursor(0, GRID_JOINT_SIZE - 1),
+            Key::Down | Key::Char('j') => self.move_cursor(0, 1),
+            Key::Left | Key::Char('h') => self.move_cursor(GRID_JOINT_SIZE - 1, 0),
+            Key::Right | Key::Char('l') => self.move_cursor(1, 0),
+            Key::Char(character @ '1'..='9') => {
+                let value = character.to_digit(10).unwrap();
+                let result = if self.pencil_mode {
+                    self.game.toggle_pencil_mark(x, y, value)
+                } else {
+                    self.game.place_digit(x, y, value)
+                };
+                self.handle_edit(result);
+            }
+            Key::Char('0') | Key::Char('x') | Key::Char(' ') | Key::Delete => {
+                let result = self.game.clear_digit(x, y);
+                self.handle_edit(result);
+            }
+            Key::Char('a') => {
+                let result = self.game.fill_candidates();
+                self.handle_edit(result);
+            }
+            Key::Char('p') => self.pencil_mode = !self.pencil_mode,
+            Key::Char('u') => {
+                if !self.game.undo() {
+                    self.message = "Nothing to undo".to_owned();
+                }
+            }
+            Key::Char('r') => {
+                if !self.game.redo() {
+                    self.message = "Nothing to redo".to_owned();
+                }
+            }
+            Key::Char('?') => self.hint(),
+            Key::Char('c') => self.check(),
+            Key::Char('q') | Key::Interrupt => return Action::Quit,
+            Key::Escape | Key::Char(_) => {}
+        }
+        Action::Continue
+    }
+
+    fn move_cursor(&mut self, x_offset: usize, y_offset: usize) {
+        self.cursor_x = (self.cursor_x + x_offset) % GRID_JOINT_SIZE;
+        self.cursor_y = (self.cursor_y + y_offset) % GRID_JOINT_SIZE;
+    }
+
+    fn handle_edit(&mut self, result: GameResult) {
+        match result {
+            Ok(()) => {
+                self.checked = false;
+                if self.game.is_solved() {
+                    self.game.pause();
+                    self.message = "Congratulations, the puzzle is solved!".to_owned();
+                }
+            }
+            Err(error) => self.message = error.into_owned(),
+        }
+    }
+
+    fn hint(&mut self) {
+        let solution = match self.game.solution() {
+            Some(solution) => solution,
+            None => {
+                self.message = "The puzzle has no unique solution".to_owned();
+                return;
+            }
+        };
+        match find_hint(self.game.grid(), solution) {
+            Some((x, y, value)) => {
+                self.cursor_x = x;
+                self.cursor_y = y;
+                self.message = format!("Hint: r{}c{} = {}", y + 1, x + 1, value);
+            }
+            None => self.message = "There are no empty cells left".to_owned(),
+        }
+    }
+
+    fn check(&mut self) {
+        if self.game.solution().is_none() {
+            self.message = "The puzzle has no unique solution".to_owned();
+            return;
+        }
+        let mut mistakes = 0;
+        let mut empty_cells = 0;
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                match self.game.grid().get_digit(x, y) {
+                    Digit::Unknown(_) => empty_cells += 1,
+                    Digit::Known(_) if self.game.is_mistake(x, y) => mistakes += 1,
+                    Digit::Known(_) => {}
+                }
+            }
+        }
+        self.checked = true;
+        self.message = format!("{} wrong digits, {} empty cells", mistakes, empty_cells);
+    }
+
+    fn is_conflicting(&self, x: usize, y: usize) -> bool {
+        let grid = self.game.grid();
+        let digit = grid.get_digit(x, y);
+        match digit {
+            Digit::Known(_) => grid
+                .get_unit_neighbour_digits(self.game.units(), x, y)
+                .contains(&digit),
+            Digit::Unknown(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sudoku_solver::grid::digit::UnknownDigit;
+    use sudoku_solver::grid::parser::Parser;
+
+    use super::*;
+
+    fn create_play() -> Play {
+        let syntax = include_str!("../../../tests/grids/easy.grid").to_owned();
+        let grid = Parser::new().parse(syntax).unwrap();
+        Play::new(grid)
+    }
+
+    #[test]
+    fn test_digits_can_be_placed_undone_and_redone() {
+        let mut play = create_play();
+        play.handle_key(Key::Char('4'));
+        assert_eq!(&Digit::Known(4), play.game.grid().get_digit(0, 0));
+        play.handle_key(Key::Char('u'));
+        assert_eq!(play.game.initial_grid(), play.game.grid());
+        play.handle_key(Key::Char('r'));
+        assert_eq!(&Digit::Known(4), play.game.grid().get_digit(0, 0));
+    }
+
+    #[test]
+    fn test_given_digits_cannot_be_changed() {
+        let mut play = create_play();
+        play.handle_key(Key::Right);
+        play.handle_key(Key::Char('5'));
+        play.handle_key(Key::Delete);
+        assert_eq!(play.game.initial_grid(), play.game.grid());
+        assert!(!play.game.can_undo());
+    }
+
+    #[test]
+    fn test_pencil_marks_are_toggled() {
+        let mut play = create_play();
+        play.handle_key(Key::Char('p'));
+        play.handle_key(Key::Char('6'));
+        play.handle_key(Key::Char('4'));
+        play.handle_key(Key::Char('6'));
+        let expected = Digit::Unknown(UnknownDigit::new(vec![4]));
+        assert_eq!(&expected, play.game.grid().get_digit(0, 0));
+    }
+
+    #[test]
+    fn test_conflicts_and_mistakes_are_detected() {
+        let mut play = create_play();
+        play.handle_key(Key::Char('7'));
+        assert!(play.is_conflicting(0, 0));
+        assert!(play.game.is_mistake(0, 0));
+        play.handle_key(Key::Char('6'));
+        assert!(!play.is_conflicting(0, 0));
+        assert!(play.game.is_mistake(0, 0));
+        assert_eq!(2, play.game.mistakes());
+    }
+}
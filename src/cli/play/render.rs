@@ -0,0 +1,138 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use sudoku_solver::grid::digit::Digit;
+use sudoku_solver::grid::{GRID_JOINT_SIZE, SUBGRID_SIZE};
+
+use crate::cli::play::Play;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const REVERSE: &str = "\x1b[7m";
+const CYAN: &str = "\x1b[36m";
+const RED_BACKGROUND: &str = "\x1b[41m";
+const YELLOW_BACKGROUND: &str = "\x1b[43m";
+
+const CELL_HEIGHT: usize = 3;
+
+impl Play {
+    pub(super) fn render(&self) -> String {
+        let mut result = String::new();
+        result.push_str(&Self::render_border('╔', '═', '╤', '╦', '╗'));
+        for y in 0..GRID_JOINT_SIZE {
+            for line in 0..CELL_HEIGHT {
+                result.push('║');
+                for x in 0..GRID_JOINT_SIZE {
+                    result.push_str(&self.render_cell_line(x, y, line));
+                    result.push(if x % SUBGRID_SIZE == SUBGRID_SIZE - 1 {
+                        '║'
+                    } else {
+                        '│'
+                    });
+                }
+                result.push('\n');
+            }
+            let border = if y == GRID_JOINT_SIZE - 1 {
+                Self::render_border('╚', '═', '╧', '╩', '╝')
+            } else if y % SUBGRID_SIZE == SUBGRID_SIZE - 1 {
+                Self::render_border('╠', '═', '╪', '╬', '╣')
+            } else {
+                Self::render_border('╟', '─', '┼', '╫', '╢')
+            };
+            result.push_str(&border);
+        }
+        self.render_status(&mut result);
+        result
+    }
+
+    fn render_border(
+        left: char,
+        line: char,
+        cross: char,
+        subgrid_cross: char,
+        right: char,
+    ) -> String {
+        let mut result = String::new();
+        result.push(left);
+        for x in 0..GRID_JOINT_SIZE {
+            result.extend(std::iter::repeat(line).take(CELL_HEIGHT));
+            result.push(if x == GRID_JOINT_SIZE - 1 {
+                right
+            } else if x % SUBGRID_SIZE == SUBGRID_SIZE - 1 {
+                subgrid_cross
+            } else {
+                cross
+            });
+        }
+        result.push('\n');
+        result
+    }
+
+    fn render_cell_line(&self, x: usize, y: usize, line: usize) -> String {
+        let mut style = String::new();
+        if (x, y) == (self.cursor_x, self.cursor_y) {
+            style.push_str(REVERSE);
+        }
+        if self.is_conflicting(x, y) {
+            style.push_str(RED_BACKGROUND);
+        } else if self.checked && self.game.is_mistake(x, y) {
+            style.push_str(YELLOW_BACKGROUND);
+        }
+        let content = match self.game.grid().get_digit(x, y) {
+            Digit::Known(value) if line == CELL_HEIGHT / 2 => {
+                let color = if self.game.is_given(x, y) { BOLD } else { CYAN };
+                format!("{} {} ", color, value)
+            }
+            Digit::Known(_) => "   ".to_owned(),
+            Digit::Unknown(digit) => {
+                let marks = (1..=CELL_HEIGHT as u32)
+                    .map(|column| line as u32 * CELL_HEIGHT as u32 + column)
+                    .map(|value| match digit.possible_values.contains(&value) {
+                        true => std::char::from_digit(value, 10).unwrap(),
+                        false => ' ',
+                    })
+                    .collect::<String>();
+                format!("{}{}", DIM, marks)
+            }
+        };
+        format!("{}{}{}", style, content, RESET)
+    }
+
+    fn render_status(&self, result: &mut String) {
+        let mode = if self.pencil_mode { "pencil" } else { "digit" };
+        let seconds = self.game.elapsed().as_secs();
+        result.push_str(&format!(
+            "Mode: {}  Time: {:02}:{:02}  Mistakes: {}\n",
+            mode,
+            seconds / 60,
+            seconds % 60,
+            self.game.mistakes()
+        ));
+        result.push_str(&format!("{}\n\n", self.message));
+        result
+            .push_str("arrows/hjkl move  1-9 enter  0/x clear  p pencil mode  a fill candidates\n");
+        result.push_str("u undo  r redo  ? hint  c check  q quit\n");
+    }
+}
@@ -0,0 +1,183 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufReader, Bytes, Read, Write};
+use std::process::{Command, Stdio};
+
+const TERMINAL_PATH: &str = "/dev/tty";
+
+const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h\x1b[?25l";
+const LEAVE_ALTERNATE_SCREEN: &str = "\x1b[?25h\x1b[?1049l";
+const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Delete,
+    Escape,
+    Interrupt,
+    Char(char),
+}
+
+pub struct Terminal {
+    input: Bytes<BufReader<File>>,
+    output: File,
+    saved_settings: String,
+}
+
+impl Terminal {
+    pub fn open() -> io::Result<Self> {
+        let input = File::open(TERMINAL_PATH)?;
+        let output = OpenOptions::new().write(true).open(TERMINAL_PATH)?;
+        let saved_settings = stty(&["-g"])?;
+        // Reads give up after a tenth of a second, so that a lone Esc press is not mistaken for
+        // the start of an escape sequence completed by the next key.
+        stty(&["raw", "-echo", "min", "0", "time", "1"])?;
+        let mut terminal = Self {
+            input: BufReader::new(input).bytes(),
+            output,
+            saved_settings: saved_settings.trim().to_owned(),
+        };
+        terminal.write(ENTER_ALTERNATE_SCREEN)?;
+        Ok(terminal)
+    }
+
+    pub fn draw(&mut self, screen: &str) -> io::Result<()> {
+        // Raw mode disables the translation of line feeds, so every line has to return the
+        // cursor to the first column explicitly.
+        let screen = screen.replace('\n', "\r\n");
+        self.write(CLEAR_SCREEN)?;
+        self.write(&screen)
+    }
+
+    pub fn read_key(&mut self) -> io::Result<Key> {
+        loop {
+            if let Some(key) = decode_key(&mut self.input)? {
+                return Ok(key);
+            }
+        }
+    }
+
+    fn write(&mut self, text: &str) -> io::Result<()> {
+        self.output.write_all(text.as_bytes())?;
+        self.output.flush()
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // Restoring the terminal is best effort, there is nothing left to do if it fails.
+        let _ = self.write(LEAVE_ALTERNATE_SCREEN);
+        let _ = stty(&[&self.saved_settings]);
+    }
+}
+
+fn stty(arguments: &[&str]) -> io::Result<String> {
+    let terminal = File::open(TERMINAL_PATH)?;
+    let output = Command::new("stty")
+        .args(arguments)
+        .stdin(Stdio::from(terminal))
+        .output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let message = String::from_utf8_lossy(&output.stderr).into_owned();
+        Err(io::Error::other(message))
+    }
+}
+
+// Returns no key when no byte arrives before the read times out or the byte is not a known key.
+pub fn decode_key(bytes: &mut impl Iterator<Item = io::Result<u8>>) -> io::Result<Option<Key>> {
+    let byte = match bytes.next() {
+        Some(byte) => byte?,
+        None => return Ok(None),
+    };
+    let key = match byte {
+        0x03 | 0x04 => Some(Key::Interrupt),
+        0x08 | 0x7f => Some(Key::Delete),
+        0x1b => decode_escape_sequence(bytes)?,
+        _ if byte.is_ascii() && !byte.is_ascii_control() => Some(Key::Char(byte as char)),
+        _ => None,
+    };
+    Ok(key)
+}
+
+fn decode_escape_sequence(
+    bytes: &mut impl Iterator<Item = io::Result<u8>>,
+) -> io::Result<Option<Key>> {
+    match bytes.next().transpose()? {
+        Some(b'[') => {}
+        Some(_) => return Ok(None),
+        None => return Ok(Some(Key::Escape)),
+    }
+    let mut next = || bytes.next().transpose().map(Option::unwrap_or_default);
+    let key = match next()? {
+        b'A' => Some(Key::Up),
+        b'B' => Some(Key::Down),
+        b'C' => Some(Key::Right),
+        b'D' => Some(Key::Left),
+        b'3' if next()? == b'~' => Some(Key::Delete),
+        _ => None,
+    };
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+
+    fn decode(bytes: &[u8]) -> Option<Key> {
+        let mut bytes = bytes.iter().map(|byte| Ok(*byte));
+        decode_key(&mut bytes).unwrap()
+    }
+
+    #[test]
+    fn test_keys_are_decoded() {
+        assert_eq!(Some(Key::Char('5')), decode(b"5"));
+        assert_eq!(Some(Key::Up), decode(b"\x1b[A"));
+        assert_eq!(Some(Key::Left), decode(b"\x1b[D"));
+        assert_eq!(Some(Key::Delete), decode(b"\x1b[3~"));
+        assert_eq!(Some(Key::Delete), decode(b"\x7f"));
+        assert_eq!(Some(Key::Interrupt), decode(b"\x03"));
+        assert_eq!(None, decode(b"\x1b[Z"));
+        assert_eq!(None, decode(b""));
+    }
+
+    #[test]
+    fn test_lone_escape_does_not_swallow_the_next_key() {
+        assert_eq!(Some(Key::Escape), decode(b"\x1b"));
+        // The read times out between the presses, so the input yields nothing once.
+        let mut reads = vec![Some(0x1b), None, Some(b'5')].into_iter();
+        let mut bytes = iter::from_fn(move || reads.next().flatten().map(Ok));
+        assert_eq!(Some(Key::Escape), decode_key(&mut bytes).unwrap());
+        assert_eq!(Some(Key::Char('5')), decode_key(&mut bytes).unwrap());
+    }
+}
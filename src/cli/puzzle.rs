@@ -0,0 +1,95 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use sudoku_solver::grid::digit::Digit;
+use sudoku_solver::grid::{Grid, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_SIZE};
+use sudoku_solver::solver::config::SolverConfig;
+use sudoku_solver::solver::Solver;
+
+use crate::cli::random::Random;
+
+pub struct PuzzleGenerator {
+    random: Random,
+}
+
+impl PuzzleGenerator {
+    pub fn new(seed: u64) -> Self {
+        let random = Random::new(seed);
+        Self { random }
+    }
+
+    pub fn generate(&mut self) -> Grid {
+        let mut puzzle = self.generate_solution();
+        let mut cells = (0..GRID_JOINT_SIZE)
+            .flat_map(|y| (0..GRID_JOINT_SIZE).map(move |x| (x, y)))
+            .collect::<Vec<_>>();
+        self.random.shuffle(&mut cells);
+        for (x, y) in cells {
+            let digit = puzzle.get_digit(x, y).clone();
+            puzzle.set_digit(x, y, Digit::default());
+            if !is_solvable_by_deduction(&puzzle) {
+                puzzle.set_digit(x, y, digit);
+            }
+        }
+        puzzle
+    }
+
+    fn generate_solution(&mut self) -> Grid {
+        let mut values = (1..=GRID_JOINT_SIZE as u32).collect::<Vec<_>>();
+        self.random.shuffle(&mut values);
+        let rows = self.generate_line_order();
+        let columns = self.generate_line_order();
+        let mut grid = Grid::default();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, column) in columns.iter().enumerate() {
+                let pattern = (SUBGRID_SIZE * (row % SUBGRID_SIZE) + row / SUBGRID_SIZE + column)
+                    % GRID_JOINT_SIZE;
+                let digit = Digit::Known(values[pattern]);
+                grid.set_digit(x, y, digit);
+            }
+        }
+        grid
+    }
+
+    fn generate_line_order(&mut self) -> Vec<usize> {
+        let mut bands = (0..GRID_SIZE).collect::<Vec<_>>();
+        self.random.shuffle(&mut bands);
+        let mut order = Vec::with_capacity(GRID_JOINT_SIZE);
+        for band in bands {
+            let mut lines = (0..SUBGRID_SIZE)
+                .map(|line| band * SUBGRID_SIZE + line)
+                .collect::<Vec<_>>();
+            self.random.shuffle(&mut lines);
+            order.append(&mut lines);
+        }
+        order
+    }
+}
+
+// Without guessing the solver only performs sound deductions, so a grid it solves has exactly one
+// solution.
+pub fn is_solvable_by_deduction(grid: &Grid) -> bool {
+    let solver = Solver::new(grid.clone(), SolverConfig::deduction_only());
+    solver.solve().is_ok()
+}
@@ -0,0 +1,426 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::cell::OnceCell;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::grid::digit::{Digit, UnknownDigit};
+use crate::grid::generator::Generator;
+use crate::grid::parser::Parser;
+use crate::grid::units::Units;
+use crate::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+use crate::solver::config::SolverConfig;
+use crate::solver::Solver;
+
+pub type GameResult = Result<(), GameError>;
+pub type GameError = Cow<'static, str>;
+
+const SERIALIZATION_HEADER: &str = "sudoku-game 1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    PlaceDigit { x: usize, y: usize, value: u32 },
+    ClearDigit { x: usize, y: usize },
+    TogglePencilMark { x: usize, y: usize, value: u32 },
+    FillCandidates,
+}
+
+impl Display for Move {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::PlaceDigit { x, y, value } => write!(formatter, "place {} {} {}", x, y, value),
+            Move::ClearDigit { x, y } => write!(formatter, "clear {} {}", x, y),
+            Move::TogglePencilMark { x, y, value } => {
+                write!(formatter, "pencil {} {} {}", x, y, value)
+            }
+            Move::FillCandidates => write!(formatter, "fill"),
+        }
+    }
+}
+
+impl Move {
+    fn parse(syntax: &str) -> Result<Self, GameError> {
+        let mut words = syntax.split_whitespace();
+        let name = words.next().unwrap_or_default();
+        let arguments = words.collect::<Vec<_>>();
+        let game_move = match (name, arguments.as_slice()) {
+            ("place", [x, y, value]) => Move::PlaceDigit {
+                x: Self::parse_number(x, syntax)?,
+                y: Self::parse_number(y, syntax)?,
+                value: Self::parse_number(value, syntax)?,
+            },
+            ("clear", [x, y]) => Move::ClearDigit {
+                x: Self::parse_number(x, syntax)?,
+                y: Self::parse_number(y, syntax)?,
+            },
+            ("pencil", [x, y, value]) => Move::TogglePencilMark {
+                x: Self::parse_number(x, syntax)?,
+                y: Self::parse_number(y, syntax)?,
+                value: Self::parse_number(value, syntax)?,
+            },
+            ("fill", []) => Move::FillCandidates,
+            _ => return Err(format!("Invalid move: '{}'", syntax).into()),
+        };
+        Ok(game_move)
+    }
+
+    fn parse_number<T: FromStr>(word: &str, syntax: &str) -> Result<T, GameError> {
+        word.parse()
+            .map_err(|_| format!("Invalid move: '{}'", syntax).into())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Game {
+    initial_grid: Grid,
+    grid: Grid,
+    // The constraints never change during a game, so neither do the units.
+    units: Units,
+    // Solving may take a while, so it is only done once a check needs the solution.
+    solution: OnceCell<Option<Grid>>,
+    moves: Vec<Move>,
+    position: usize,
+    mistakes: u32,
+    elapsed: Duration,
+    resumed_at: Option<Instant>,
+}
+
+impl Game {
+    pub fn new(grid: Grid) -> Self {
+        let initial_grid = Self::to_givens(&grid);
+        Self {
+            grid: initial_grid.clone(),
+            units: initial_grid.get_units(),
+            initial_grid,
+            solution: OnceCell::new(),
+            moves: Vec::new(),
+            position: 0,
+            mistakes: 0,
+            elapsed: Duration::default(),
+            resumed_at: Some(Instant::now()),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn initial_grid(&self) -> &Grid {
+        &self.initial_grid
+    }
+
+    pub fn units(&self) -> &Units {
+        &self.units
+    }
+
+    pub fn solution(&self) -> Option<&Grid> {
+        self.solution
+            .get_or_init(|| Self::find_solution(&self.initial_grid))
+            .as_ref()
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.moves[..self.position]
+    }
+
+    pub fn mistakes(&self) -> u32 {
+        self.mistakes
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        match self.resumed_at {
+            Some(resumed_at) => self.elapsed + resumed_at.elapsed(),
+            None => self.elapsed,
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.resumed_at.is_none()
+    }
+
+    pub fn pause(&mut self) {
+        if let Some(resumed_at) = self.resumed_at.take() {
+            self.elapsed += resumed_at.elapsed();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if self.resumed_at.is_none() {
+            self.resumed_at = Some(Instant::now());
+        }
+    }
+
+    pub fn is_given(&self, x: usize, y: usize) -> bool {
+        matches!(self.initial_grid.get_digit(x, y), Digit::Known(_))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grid.is_solved() && self.grid.is_solved_correctly()
+    }
+
+    pub fn is_mistake(&self, x: usize, y: usize) -> bool {
+        let digit = self.grid.get_digit(x, y);
+        match (digit, self.solution()) {
+            (Digit::Known(_), Some(solution)) => solution.get_digit(x, y) != digit,
+            (Digit::Known(_), None) => self
+                .grid
+                .get_unit_neighbour_digits(&self.units, x, y)
+                .contains(&digit),
+            (Digit::Unknown(_), _) => false,
+        }
+    }
+
+    pub fn place_digit(&mut self, x: usize, y: usize, value: u32) -> GameResult {
+        self.apply(Move::PlaceDigit { x, y, value })
+    }
+
+    pub fn clear_digit(&mut self, x: usize, y: usize) -> GameResult {
+        self.apply(Move::ClearDigit { x, y })
+    }
+
+    pub fn toggle_pencil_mark(&mut self, x: usize, y: usize, value: u32) -> GameResult {
+        self.apply(Move::TogglePencilMark { x, y, value })
+    }
+
+    pub fn fill_candidates(&mut self) -> GameResult {
+        self.apply(Move::FillCandidates)
+    }
+
+    pub fn apply(&mut self, game_move: Move) -> GameResult {
+        self.validate_move(game_move)?;
+        let previous_grid = self.grid.clone();
+        Self::perform(&mut self.grid, game_move);
+        if self.grid == previous_grid {
+            return Ok(());
+        }
+        if let Move::PlaceDigit { x, y, .. } = game_move {
+            if self.is_mistake(x, y) {
+                self.mistakes += 1;
+            }
+        }
+        self.moves.truncate(self.position);
+        self.moves.push(game_move);
+        self.position += 1;
+        Ok(())
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.moves.len()
+    }
+
+    pub fn undo(&mut self) -> bool {
+        if !self.can_undo() {
+            return false;
+        }
+        self.position -= 1;
+        self.replay();
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        if !self.can_redo() {
+            return false;
+        }
+        Self::perform(&mut self.grid, self.moves[self.position]);
+        self.position += 1;
+        true
+    }
+
+    pub fn serialize(&self) -> String {
+        let generator = Generator::new();
+        // Line generation cannot fail, it only returns a result to match the other formats.
+        let grid = generator.generate_line(&self.initial_grid).unwrap();
+        let mut lines = grid.lines();
+        let mut result = String::new();
+        result.push_str(SERIALIZATION_HEADER);
+        result.push('\n');
+        result.push_str(&format!("grid {}\n", lines.next().unwrap_or_default()));
+        for declaration in lines {
+            result.push_str(&format!("constraint {}\n", declaration));
+        }
+        result.push_str(&format!("elapsed {}\n", self.elapsed().as_millis()));
+        result.push_str(&format!("mistakes {}\n", self.mistakes));
+        result.push_str(&format!("position {}\n", self.position));
+        for game_move in &self.moves {
+            result.push_str(&format!("move {}\n", game_move));
+        }
+        result
+    }
+
+    pub fn deserialize(syntax: &str) -> Result<Self, GameError> {
+        let mut lines = syntax
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty());
+        if lines.next() != Some(SERIALIZATION_HEADER) {
+            return Err("Missing game header".into());
+        }
+        let mut grid = None;
+        let mut declarations = Vec::new();
+        let mut elapsed = Duration::default();
+        let mut mistakes = 0;
+        let mut position = None;
+        let mut moves = Vec::new();
+        for line in lines {
+            let (key, value) = line.split_at(line.find(' ').unwrap_or(line.len()));
+            let value = value.trim();
+            let invalid_value = || format!("Invalid value of '{}': '{}'", key, value);
+            match key {
+                "grid" => grid = Some(value),
+                "constraint" => declarations.push(value),
+                "elapsed" => {
+                    let millis = value.parse().map_err(|_| invalid_value())?;
+                    elapsed = Duration::from_millis(millis);
+                }
+                "mistakes" => mistakes = value.parse().map_err(|_| invalid_value())?,
+                "position" => position = Some(value.parse().map_err(|_| invalid_value())?),
+                "move" => moves.push(Move::parse(value)?),
+                _ => return Err(format!("Unknown key: '{}'", key).into()),
+            }
+        }
+        let mut grid = grid.ok_or("Missing grid")?.to_owned();
+        for declaration in declarations {
+            grid.push('\n');
+            grid.push_str(declaration);
+        }
+        let grid = Parser::new().parse_line(grid)?;
+        let position = position.unwrap_or(moves.len());
+        if position > moves.len() {
+            return Err("Position exceeds the number of moves".into());
+        }
+        let mut game = Self::new(grid);
+        for game_move in &moves {
+            game.validate_move(*game_move)?;
+            Self::perform(&mut game.grid, *game_move);
+        }
+        game.moves = moves;
+        game.position = position;
+        game.mistakes = mistakes;
+        game.elapsed = elapsed;
+        game.replay();
+        Ok(game)
+    }
+
+    fn validate_move(&self, game_move: Move) -> GameResult {
+        let (x, y, value) = match game_move {
+            Move::PlaceDigit { x, y, value } | Move::TogglePencilMark { x, y, value } => {
+                (x, y, Some(value))
+            }
+            Move::ClearDigit { x, y } => (x, y, None),
+            Move::FillCandidates => return Ok(()),
+        };
+        if x >= GRID_JOINT_SIZE || y >= GRID_JOINT_SIZE {
+            return Err(format!("Cell ({}, {}) is outside of the grid", x, y).into());
+        }
+        if let Some(value) = value {
+            if !(1..=SUBGRID_LENGTH as u32).contains(&value) {
+                return Err(format!("Invalid value: {}", value).into());
+            }
+        }
+        if self.is_given(x, y) {
+            return Err("Given digits cannot be changed".into());
+        }
+        if let (Move::TogglePencilMark { .. }, Digit::Known(_)) =
+            (game_move, self.grid.get_digit(x, y))
+        {
+            return Err("Pencil marks can only be added to empty cells".into());
+        }
+        Ok(())
+    }
+
+    fn perform(grid: &mut Grid, game_move: Move) {
+        match game_move {
+            Move::PlaceDigit { x, y, value } => grid.set_digit(x, y, Digit::Known(value)),
+            Move::ClearDigit { x, y } => grid.set_digit(x, y, Digit::default()),
+            Move::TogglePencilMark { x, y, value } => {
+                if let Digit::Unknown(digit) = grid.get_digit_mut(x, y) {
+                    let values = &mut digit.possible_values;
+                    match values.binary_search(&value) {
+                        Ok(index) => {
+                            values.remove(index);
+                        }
+                        Err(index) => values.insert(index, value),
+                    }
+                }
+            }
+            Move::FillCandidates => Self::fill_grid_candidates(grid),
+        }
+    }
+
+    fn fill_grid_candidates(grid: &mut Grid) {
+        let units = grid.get_units();
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Known(_) = grid.get_digit(x, y) {
+                    continue;
+                }
+                let neighbours = grid.get_unit_neighbour_digits(&units, x, y);
+                let candidates = (1..=SUBGRID_LENGTH as u32)
+                    .filter(|value| !neighbours.contains(&&Digit::Known(*value)))
+                    .collect();
+                grid.set_digit(x, y, Digit::Unknown(UnknownDigit::new(candidates)));
+            }
+        }
+    }
+
+    fn replay(&mut self) {
+        self.grid = self.initial_grid.clone();
+        for game_move in &self.moves[..self.position] {
+            Self::perform(&mut self.grid, *game_move);
+        }
+    }
+
+    fn to_givens(grid: &Grid) -> Grid {
+        let mut givens = grid.clone();
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Unknown(_) = givens.get_digit(x, y) {
+                    givens.set_digit(x, y, Digit::default());
+                }
+            }
+        }
+        givens
+    }
+
+    fn find_solution(grid: &Grid) -> Option<Grid> {
+        let solver = Solver::new(grid.clone(), SolverConfig::default());
+        let mut solutions = solver.find_solutions(2).ok()?;
+        if solutions.len() == 1 {
+            solutions.pop()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
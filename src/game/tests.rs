@@ -0,0 +1,149 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::parser::Parser;
+
+use super::*;
+
+type TestResult = Result<(), GameError>;
+
+fn create_game() -> Game {
+    let syntax = include_str!("../../tests/grids/easy.grid").to_owned();
+    let grid = Parser::new().parse(syntax).unwrap();
+    Game::new(grid)
+}
+
+#[test]
+fn test_moves_are_undone_and_redone() -> TestResult {
+    let mut game = create_game();
+    game.place_digit(0, 0, 6)?;
+    game.toggle_pencil_mark(3, 0, 8)?;
+    game.clear_digit(0, 0)?;
+    assert_eq!(3, game.moves().len());
+    assert!(game.undo());
+    assert_eq!(&Digit::Known(6), game.grid().get_digit(0, 0));
+    assert!(game.undo());
+    assert!(game.undo());
+    assert!(!game.undo());
+    assert_eq!(game.initial_grid(), game.grid());
+    assert!(game.redo());
+    assert!(game.redo());
+    let expected = Digit::Unknown(UnknownDigit::new(vec![8]));
+    assert_eq!(&expected, game.grid().get_digit(3, 0));
+    Ok(())
+}
+
+#[test]
+fn test_new_move_discards_redo_history() -> TestResult {
+    let mut game = create_game();
+    game.place_digit(0, 0, 6)?;
+    game.undo();
+    game.place_digit(0, 0, 8)?;
+    assert!(!game.can_redo());
+    assert_eq!(
+        &[Move::PlaceDigit {
+            x: 0,
+            y: 0,
+            value: 8
+        }],
+        game.moves()
+    );
+    Ok(())
+}
+
+#[test]
+fn test_givens_cannot_be_changed() {
+    let mut game = create_game();
+    assert!(game.place_digit(1, 0, 3).is_err());
+    assert!(game.clear_digit(1, 0).is_err());
+    assert!(game.toggle_pencil_mark(1, 0, 3).is_err());
+    assert!(game.place_digit(9, 0, 3).is_err());
+    assert!(game.place_digit(0, 0, 10).is_err());
+    assert!(game.moves().is_empty());
+}
+
+#[test]
+fn test_mistakes_are_counted() -> TestResult {
+    let mut game = create_game();
+    game.place_digit(0, 0, 6)?;
+    game.place_digit(0, 0, 8)?;
+    assert!(!game.is_mistake(0, 0));
+    game.undo();
+    assert_eq!(1, game.mistakes());
+    assert!(game.is_mistake(0, 0));
+    Ok(())
+}
+
+#[test]
+fn test_candidates_are_filled() -> TestResult {
+    let mut game = create_game();
+    game.fill_candidates()?;
+    let expected = Digit::Unknown(UnknownDigit::new(vec![6, 8]));
+    assert_eq!(&expected, game.grid().get_digit(0, 0));
+    Ok(())
+}
+
+#[test]
+fn test_game_is_serialized_and_resumed() -> TestResult {
+    let mut game = create_game();
+    game.place_digit(0, 0, 6)?;
+    game.toggle_pencil_mark(3, 0, 5)?;
+    game.fill_candidates()?;
+    game.undo();
+    game.pause();
+    let syntax = game.serialize();
+    let resumed_game = Game::deserialize(&syntax)?;
+    assert_eq!(game.grid(), resumed_game.grid());
+    assert_eq!(game.moves(), resumed_game.moves());
+    assert_eq!(1, resumed_game.mistakes());
+    assert!(resumed_game.elapsed() >= Duration::from_millis(game.elapsed().as_millis() as u64));
+    let mut resumed_game = resumed_game;
+    assert!(resumed_game.redo());
+    assert!(!resumed_game.can_redo());
+    Ok(())
+}
+
+#[test]
+fn test_variant_constraints_are_serialized() -> TestResult {
+    let syntax = include_str!("../../tests/grids/diagonal.grid").to_owned();
+    let game = Game::new(Parser::new().parse(syntax)?);
+    assert!(game.solution().is_some());
+    let syntax = game.serialize();
+    assert!(syntax.contains("\nconstraint diagonal\n"));
+    let resumed_game = Game::deserialize(&syntax)?;
+    assert!(resumed_game.grid().constraints.diagonal);
+    assert_eq!(game.solution(), resumed_game.solution());
+    Ok(())
+}
+
+#[test]
+fn test_invalid_serialization_is_rejected() {
+    assert!(Game::deserialize("").is_err());
+    assert!(Game::deserialize("sudoku-game 1\nposition 0\n").is_err());
+    let grid = "072009310510002089094031750060050230201000500030284001020410000007028105000060900";
+    let syntax = format!("sudoku-game 1\ngrid {}\nmove place 1 0 4\n", grid);
+    assert!(Game::deserialize(&syntax).is_err());
+    let syntax = format!("sudoku-game 1\ngrid {}\nmove jump\n", grid);
+    assert!(Game::deserialize(&syntax).is_err());
+}
@@ -0,0 +1,296 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use crate::grid::cell::Cell;
+use crate::grid::transform::{TransformResult, Transformation};
+use crate::grid::{Grid, GRID_CELLS, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_LENGTH, SUBGRID_SIZE};
+
+// Finds the transformation mapping the grid to the smallest row-major reading of its digits, with
+// empty cells read as 0, among all orders of the bands, stacks, and the rows and columns within
+// them, with and without transposition. For a fixed order of the cells the smallest relabelling
+// numbers the digits in the order they are read, so only the rows are chosen one by one, keeping
+// every arrangement that ties for the smallest reading so far.
+pub fn find_canonical_transformation(grid: &Grid) -> TransformResult<Transformation> {
+    if !grid.constraints.is_classic() {
+        return Err("Only grids without variant constraints have a canonical form".into());
+    }
+    let values = grid.get_values();
+    let transposed_values = Cell::all()
+        .map(|cell| values[Cell::new(cell.row(), cell.column()).index()])
+        .collect::<Vec<_>>();
+    let orientations = [values, transposed_values];
+    let column_orders = get_line_orders();
+    let mut arrangements = Vec::with_capacity(orientations.len() * column_orders.len());
+    for orientation in 0..orientations.len() {
+        for columns in 0..column_orders.len() {
+            arrangements.push(Arrangement::new(orientation, columns));
+        }
+    }
+    for _ in 0..GRID_JOINT_SIZE {
+        let mut smallest = None;
+        let mut next_arrangements = Vec::new();
+        let mut seen = HashSet::new();
+        for arrangement in &arrangements {
+            let values = &orientations[arrangement.orientation];
+            let columns = &column_orders[arrangement.columns];
+            for row in (0..GRID_JOINT_SIZE).filter(|row| arrangement.can_take(*row)) {
+                let mut next = *arrangement;
+                let reading = next.read_row(values, columns, row);
+                match smallest.as_ref().map(|smallest| reading.cmp(smallest)) {
+                    Some(Ordering::Greater) => continue,
+                    Some(Ordering::Equal) => {}
+                    Some(Ordering::Less) | None => {
+                        smallest = Some(reading);
+                        next_arrangements.clear();
+                        seen.clear();
+                    }
+                }
+                // Arrangements with the same remaining rows, columns and labels read the same from
+                // here on.
+                if seen.insert(next.get_key()) {
+                    next_arrangements.push(next);
+                }
+            }
+        }
+        arrangements = next_arrangements;
+    }
+    Ok(arrangements[0].into_transformation(&column_orders[arrangements[0].columns]))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Arrangement {
+    orientation: usize,
+    columns: usize,
+    rows: [usize; GRID_JOINT_SIZE],
+    row_count: usize,
+    used_rows: u16,
+    labels: [u32; SUBGRID_LENGTH + 1],
+    label_count: u32,
+}
+
+impl Arrangement {
+    fn new(orientation: usize, columns: usize) -> Self {
+        Self {
+            orientation,
+            columns,
+            rows: [0; GRID_JOINT_SIZE],
+            row_count: 0,
+            used_rows: 0,
+            labels: [0; SUBGRID_LENGTH + 1],
+            label_count: 0,
+        }
+    }
+
+    // Rows are taken band by band: the first row of a band may come from any unused band, while
+    // the others complete the band of the previous row.
+    fn can_take(&self, row: usize) -> bool {
+        let band = row / SUBGRID_SIZE * SUBGRID_SIZE;
+        let is_unused = |row: usize| self.used_rows & (1 << row) == 0;
+        if self.row_count % SUBGRID_SIZE == 0 {
+            return (band..band + SUBGRID_SIZE).all(is_unused);
+        }
+        is_unused(row) && band == self.rows[self.row_count - 1] / SUBGRID_SIZE * SUBGRID_SIZE
+    }
+
+    fn read_row(
+        &mut self,
+        values: &[u32],
+        columns: &[usize],
+        row: usize,
+    ) -> [u32; GRID_JOINT_SIZE] {
+        self.rows[self.row_count] = row;
+        self.row_count += 1;
+        self.used_rows |= 1 << row;
+        let mut reading = [0; GRID_JOINT_SIZE];
+        for (label, column) in reading.iter_mut().zip(columns) {
+            let value = values[row * GRID_JOINT_SIZE + column] as usize;
+            if value == 0 {
+                continue;
+            }
+            if self.labels[value] == 0 {
+                self.label_count += 1;
+                self.labels[value] = self.label_count;
+            }
+            *label = self.labels[value];
+        }
+        reading
+    }
+
+    // Labels are below 16, so they are packed four bits each next to the used rows.
+    fn get_key(&self) -> (usize, usize, u64) {
+        let labels = self.labels[1..]
+            .iter()
+            .fold(0, |key, label| key << 4 | *label as u64);
+        let key = labels << GRID_JOINT_SIZE | self.used_rows as u64;
+        (self.orientation, self.columns, key)
+    }
+
+    fn into_transformation(self, columns: &[usize]) -> Transformation {
+        let sources = (0..GRID_CELLS)
+            .map(|index| {
+                let cell = Cell::from_index(index);
+                let (x, y) = (columns[cell.column()], self.rows[cell.row()]);
+                match self.orientation {
+                    0 => Cell::new(x, y).index(),
+                    _ => Cell::new(y, x).index(),
+                }
+            })
+            .collect();
+        // Digits missing from the grid take the remaining labels.
+        let mut labels = self.labels;
+        let mut label_count = self.label_count;
+        for label in labels.iter_mut().skip(1).filter(|label| **label == 0) {
+            label_count += 1;
+            *label = label_count;
+        }
+        Transformation::from_parts(sources, labels.to_vec())
+    }
+}
+
+// All orders of the lines that keep the lines of each band together.
+fn get_line_orders() -> Vec<Vec<usize>> {
+    let band_orders = get_permutations(GRID_SIZE);
+    let line_orders = get_permutations(SUBGRID_SIZE);
+    let mut orders = Vec::new();
+    for band_order in &band_orders {
+        let mut partial_orders = vec![Vec::with_capacity(GRID_JOINT_SIZE)];
+        for band in band_order {
+            partial_orders = partial_orders
+                .iter()
+                .flat_map(|order| {
+                    line_orders.iter().map(move |line_order| {
+                        let mut order = order.clone();
+                        order.extend(line_order.iter().map(|line| band * SUBGRID_SIZE + line));
+                        order
+                    })
+                })
+                .collect();
+        }
+        orders.extend(partial_orders);
+    }
+    orders
+}
+
+fn get_permutations(length: usize) -> Vec<Vec<usize>> {
+    if length == 0 {
+        return vec![Vec::new()];
+    }
+    let mut permutations = Vec::new();
+    for permutation in get_permutations(length - 1) {
+        for position in 0..length {
+            let mut permutation = permutation.clone();
+            permutation.insert(position, length - 1);
+            permutations.push(permutation);
+        }
+    }
+    permutations
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::constraints::Constraints;
+    use crate::grid::digit::Digit;
+    use crate::grid::parser::Parser;
+
+    use super::*;
+
+    const PUZZLE: &str =
+        "..53.....8......2..7..1.5..4....53...1..7...6..32...8..6.5....9..4....3......97..";
+
+    fn parse(syntax: &str) -> Grid {
+        Parser::new().parse_line(syntax.to_owned()).unwrap()
+    }
+
+    fn disguise(grid: &Grid) -> Grid {
+        let transformation = Transformation::rotate()
+            .then(&Transformation::swap_bands(0, 2).unwrap())
+            .then(&Transformation::swap_rows(4, 5).unwrap())
+            .then(&Transformation::swap_stacks(0, 1).unwrap())
+            .then(&Transformation::swap_columns(6, 8).unwrap())
+            .then(&Transformation::relabel(&[4, 7, 1, 9, 2, 5, 8, 3, 6]).unwrap());
+        transformation.apply(grid).unwrap()
+    }
+
+    #[test]
+    fn test_disguised_puzzles_have_the_same_canonical_form() {
+        let grid = parse(PUZZLE);
+        let disguised = disguise(&grid);
+        assert_ne!(grid, disguised);
+        let canonical = grid.get_canonical_form();
+        assert_eq!(canonical, disguised.get_canonical_form());
+        assert_eq!(canonical, canonical.get_canonical_form());
+        assert!(grid.is_equivalent(&disguised));
+        assert!(!canonical.get_values().iter().copied().gt(grid.get_values()));
+    }
+
+    #[test]
+    fn test_canonical_transformation_maps_grid_to_canonical_form() -> TransformResult<()> {
+        let grid = parse(PUZZLE);
+        let transformation = find_canonical_transformation(&grid)?;
+        let canonical = transformation.apply(&grid)?;
+        assert_eq!(grid.get_canonical_form(), canonical);
+        assert_eq!(grid, transformation.inverse().apply(&canonical)?);
+        let values = canonical.get_values();
+        let first_given = values.iter().position(|value| *value != 0).unwrap();
+        assert_eq!(1, values[first_given]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solved_grid_starts_with_ordered_digits() {
+        let grid = parse(
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179",
+        );
+        let canonical = grid.get_canonical_form();
+        assert_eq!(vec![1, 2, 3, 4, 5, 6, 7, 8, 9], canonical.get_values()[..9]);
+        assert!(canonical.is_solved_correctly());
+        assert!(grid.is_equivalent(&disguise(&grid)));
+    }
+
+    #[test]
+    fn test_different_puzzles_are_not_equivalent() {
+        let grid = parse(PUZZLE);
+        let mut other = grid.clone();
+        other.set_digit(0, 0, Digit::Known(1));
+        assert!(!grid.is_equivalent(&other));
+        assert_eq!(Grid::default(), Grid::default().get_canonical_form());
+    }
+
+    #[test]
+    fn test_variant_grids_are_only_equivalent_to_themselves() {
+        let constraints = Constraints {
+            diagonal: true,
+            ..Constraints::default()
+        };
+        let grid = Grid::from_cells(parse(PUZZLE).digits, constraints);
+        assert!(find_canonical_transformation(&grid).is_err());
+        assert_eq!(grid, grid.get_canonical_form());
+        assert!(grid.is_equivalent(&grid.clone()));
+        let classic = parse(PUZZLE);
+        assert!(!grid.is_equivalent(&classic));
+    }
+}
@@ -0,0 +1,126 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::{
+    GRID_CELLS, GRID_JOINT_SIZE, GRID_LENGTH, GRID_SIZE, SUBGRID_LENGTH, SUBGRID_SIZE,
+};
+
+// A cell of the grid identified by its row-major index, which is also its index in the grid
+// storage. Rows, columns and subgrids are numbered from the top left corner.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cell {
+    index: usize,
+}
+
+impl Cell {
+    pub fn new(x: usize, y: usize) -> Self {
+        assert!(
+            x < GRID_JOINT_SIZE && y < GRID_JOINT_SIZE,
+            "cell coordinates should be between 0 and {}",
+            GRID_JOINT_SIZE - 1
+        );
+        Self::from_index(y * GRID_JOINT_SIZE + x)
+    }
+
+    pub fn from_index(index: usize) -> Self {
+        assert!(
+            index < GRID_CELLS,
+            "cell index should be between 0 and {}",
+            GRID_CELLS - 1
+        );
+        Self { index }
+    }
+
+    pub fn from_subgrid(subgrid: usize, offset: usize) -> Self {
+        assert!(subgrid < GRID_LENGTH && offset < SUBGRID_LENGTH);
+        let x = subgrid % GRID_SIZE * SUBGRID_SIZE + offset % SUBGRID_SIZE;
+        let y = subgrid / GRID_SIZE * SUBGRID_SIZE + offset / SUBGRID_SIZE;
+        Self::new(x, y)
+    }
+
+    pub fn all() -> impl Iterator<Item = Cell> {
+        (0..GRID_CELLS).map(Self::from_index)
+    }
+
+    pub fn row_cells(row: usize) -> impl Iterator<Item = Cell> {
+        (0..GRID_JOINT_SIZE).map(move |column| Self::new(column, row))
+    }
+
+    pub fn column_cells(column: usize) -> impl Iterator<Item = Cell> {
+        (0..GRID_JOINT_SIZE).map(move |row| Self::new(column, row))
+    }
+
+    pub fn subgrid_cells(subgrid: usize) -> impl Iterator<Item = Cell> {
+        (0..SUBGRID_LENGTH).map(move |offset| Self::from_subgrid(subgrid, offset))
+    }
+
+    pub fn index(self) -> usize {
+        self.index
+    }
+
+    pub fn row(self) -> usize {
+        self.index / GRID_JOINT_SIZE
+    }
+
+    pub fn column(self) -> usize {
+        self.index % GRID_JOINT_SIZE
+    }
+
+    pub fn subgrid(self) -> usize {
+        self.row() / SUBGRID_SIZE * GRID_SIZE + self.column() / SUBGRID_SIZE
+    }
+
+    // Index of the cell within its subgrid, in row-major order.
+    pub fn subgrid_offset(self) -> usize {
+        self.row() % SUBGRID_SIZE * SUBGRID_SIZE + self.column() % SUBGRID_SIZE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_accessors() {
+        let cell = Cell::new(5, 7);
+        assert_eq!(68, cell.index());
+        assert_eq!(7, cell.row());
+        assert_eq!(5, cell.column());
+        assert_eq!(7, cell.subgrid());
+        assert_eq!(5, cell.subgrid_offset());
+        assert_eq!(cell, Cell::from_subgrid(7, 5));
+    }
+
+    #[test]
+    fn test_cells_are_iterated_by_units() {
+        let row = Cell::row_cells(2).map(Cell::index).collect::<Vec<_>>();
+        assert_eq!(vec![18, 19, 20, 21, 22, 23, 24, 25, 26], row);
+        let column = Cell::column_cells(2).map(Cell::index).collect::<Vec<_>>();
+        assert_eq!(vec![2, 11, 20, 29, 38, 47, 56, 65, 74], column);
+        let subgrid = Cell::subgrid_cells(5).map(Cell::index).collect::<Vec<_>>();
+        assert_eq!(vec![33, 34, 35, 42, 43, 44, 51, 52, 53], subgrid);
+        assert!(Cell::all()
+            .all(|cell| Cell::from_subgrid(cell.subgrid(), cell.subgrid_offset()) == cell));
+    }
+}
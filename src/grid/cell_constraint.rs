@@ -0,0 +1,524 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::cell::Cell;
+use crate::grid::{GRID_JOINT_SIZE, SUBGRID_LENGTH};
+
+// Candidates are kept as bit sets, with the bit of each possible value set.
+pub const ALL_CANDIDATES: u16 = ((1 << (SUBGRID_LENGTH + 1)) - 1) & !1;
+
+// The values between the 1 and the 9 of a line, which are all the other values at most.
+pub const MAX_SANDWICH_SUM: u32 =
+    (SUBGRID_LENGTH * (SUBGRID_LENGTH + 1) / 2 - 1 - SUBGRID_LENGTH) as u32;
+
+// A constraint on the values of a group of cells, beyond the values being distinct. Solvers and
+// the validator only use it through `restrict`, so a new kind of constraint only needs to describe
+// which values its cells can take together.
+pub trait CellConstraint {
+    fn name(&self) -> &'static str;
+
+    fn get_cells(&self) -> Vec<Cell>;
+
+    fn contains(&self, x_joint: usize, y_joint: usize) -> bool {
+        self.get_cells().contains(&Cell::new(x_joint, y_joint))
+    }
+
+    // Removes the values that cannot be part of any assignment satisfying the constraint, with
+    // `candidates` holding the candidates of the cells in the order of `get_cells`. When every
+    // cell has a single candidate and the constraint is broken, some cell is left without any.
+    fn restrict(&self, candidates: &mut [u16]);
+
+    // Checks the placed values, given in row-major order with 0 for empty cells.
+    fn is_feasible(&self, values: &[u32]) -> bool {
+        let mut candidates = get_candidates(&self.get_cells(), values);
+        self.restrict(&mut candidates);
+        candidates.iter().all(|candidates| *candidates != 0)
+    }
+
+    fn allows(&self, values: &[u32], cell: usize, value: u32) -> bool {
+        let cell = Cell::from_index(cell);
+        let cells = self.get_cells();
+        if !cells.contains(&cell) {
+            return true;
+        }
+        let mut candidates = get_candidates(&cells, values);
+        for (candidates, other) in candidates.iter_mut().zip(&cells) {
+            if *other == cell {
+                *candidates = 1 << value;
+            }
+        }
+        self.restrict(&mut candidates);
+        candidates.iter().all(|candidates| *candidates != 0)
+    }
+}
+
+fn get_candidates(cells: &[Cell], values: &[u32]) -> Vec<u16> {
+    cells
+        .iter()
+        .map(|cell| match values[cell.index()] {
+            0 => ALL_CANDIDATES,
+            value => 1 << value,
+        })
+        .collect()
+}
+
+fn get_min(candidates: u16) -> u32 {
+    candidates.trailing_zeros()
+}
+
+fn get_max(candidates: u16) -> u32 {
+    15 - candidates.leading_zeros()
+}
+
+// Returns the candidates between `min` and `max`, both inclusive.
+fn get_range(min: i64, max: i64) -> u16 {
+    let min = min.max(1);
+    let max = max.min(SUBGRID_LENGTH as i64);
+    if min > max {
+        return 0;
+    }
+    (((1u32 << (max + 1)) - 1) & !((1u32 << min) - 1)) as u16
+}
+
+// Cells that follow each other on a path must touch, at least with a corner.
+pub fn is_path(cells: &[Cell]) -> bool {
+    cells.windows(2).all(|pair| {
+        let (first, second) = (&pair[0], &pair[1]);
+        first != second
+            && first.column().abs_diff(second.column()) <= 1
+            && first.row().abs_diff(second.row()) <= 1
+    })
+}
+
+// Values strictly increase from the bulb, which is the first cell.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Thermometer {
+    pub cells: Vec<Cell>,
+}
+
+impl Thermometer {
+    pub fn new(cells: Vec<Cell>) -> Self {
+        Self { cells }
+    }
+}
+
+impl CellConstraint for Thermometer {
+    fn name(&self) -> &'static str {
+        "thermometer"
+    }
+
+    fn get_cells(&self) -> Vec<Cell> {
+        self.cells.clone()
+    }
+
+    fn restrict(&self, candidates: &mut [u16]) {
+        for index in 1..candidates.len() {
+            let lower = match candidates[index - 1] {
+                0 => SUBGRID_LENGTH as i64,
+                previous => get_min(previous) as i64,
+            };
+            candidates[index] &= get_range(lower + 1, SUBGRID_LENGTH as i64);
+        }
+        for index in (0..candidates.len() - 1).rev() {
+            let upper = match candidates[index + 1] {
+                0 => 1,
+                next => get_max(next) as i64,
+            };
+            candidates[index] &= get_range(1, upper - 1);
+        }
+    }
+}
+
+// The value in the circle, which is the first cell, is the sum of the values along the arrow.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Arrow {
+    pub circle: Cell,
+    pub cells: Vec<Cell>,
+}
+
+impl Arrow {
+    pub fn new(circle: Cell, cells: Vec<Cell>) -> Self {
+        Self { circle, cells }
+    }
+}
+
+impl CellConstraint for Arrow {
+    fn name(&self) -> &'static str {
+        "arrow"
+    }
+
+    fn get_cells(&self) -> Vec<Cell> {
+        let mut cells = vec![self.circle];
+        cells.extend(self.cells.iter().cloned());
+        cells
+    }
+
+    fn restrict(&self, candidates: &mut [u16]) {
+        let (circle, cells) = candidates.split_first_mut().unwrap();
+        if cells.contains(&0) {
+            *circle = 0;
+            return;
+        }
+        let min_sum = cells
+            .iter()
+            .map(|candidates| get_min(*candidates))
+            .sum::<u32>() as i64;
+        let max_sum = cells
+            .iter()
+            .map(|candidates| get_max(*candidates))
+            .sum::<u32>() as i64;
+        *circle &= get_range(min_sum, max_sum);
+        if *circle == 0 {
+            return;
+        }
+        let (min, max) = (get_min(*circle) as i64, get_max(*circle) as i64);
+        for candidates in cells.iter_mut() {
+            let (own_min, own_max) = (get_min(*candidates) as i64, get_max(*candidates) as i64);
+            *candidates &= get_range(min - (max_sum - own_max), max - (min_sum - own_min));
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+impl Line {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Line::Row(_) => "row",
+            Line::Column(_) => "column",
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        match self {
+            Line::Row(index) | Line::Column(index) => *index,
+        }
+    }
+
+    pub fn get_cells(&self) -> Vec<Cell> {
+        (0..GRID_JOINT_SIZE)
+            .map(|offset| match self {
+                Line::Row(y) => Cell::new(offset, *y),
+                Line::Column(x) => Cell::new(*x, offset),
+            })
+            .collect()
+    }
+}
+
+// The values between the 1 and the 9 of the line add up to the sum.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Sandwich {
+    pub line: Line,
+    pub sum: u32,
+}
+
+impl Sandwich {
+    pub fn new(line: Line, sum: u32) -> Self {
+        Self { line, sum }
+    }
+}
+
+impl CellConstraint for Sandwich {
+    fn name(&self) -> &'static str {
+        "sandwich"
+    }
+
+    fn get_cells(&self) -> Vec<Cell> {
+        self.line.get_cells()
+    }
+
+    fn contains(&self, x_joint: usize, y_joint: usize) -> bool {
+        match self.line {
+            Line::Row(y) => y == y_joint,
+            Line::Column(x) => x == x_joint,
+        }
+    }
+
+    // Every placement of the 1 and the 9 is tried, keeping the values of the placements where
+    // the cells between them can still add up to the sum.
+    fn restrict(&self, candidates: &mut [u16]) {
+        let (one, nine) = (1 << 1, 1 << SUBGRID_LENGTH);
+        let crust = one | nine;
+        let sum = self.sum as i64;
+        // The line holds distinct values, so placed values are removed from the other cells.
+        let placed = candidates
+            .iter()
+            .filter(|candidates| candidates.count_ones() == 1)
+            .fold(0, |placed, candidates| placed | candidates);
+        for candidates in candidates.iter_mut() {
+            if candidates.count_ones() != 1 {
+                *candidates &= !placed;
+            }
+        }
+        let mut allowed = vec![0; candidates.len()];
+        for first in 0..candidates.len() {
+            for second in 0..candidates.len() {
+                if first == second || candidates[first] & one == 0 || candidates[second] & nine == 0
+                {
+                    continue;
+                }
+                let (start, end) = (first.min(second) + 1, first.max(second));
+                let filling = &candidates[start..end];
+                if filling.iter().any(|candidates| candidates & !crust == 0) {
+                    continue;
+                }
+                let min_sum = filling
+                    .iter()
+                    .map(|candidates| get_min(candidates & !crust))
+                    .sum::<u32>() as i64;
+                let max_sum = filling
+                    .iter()
+                    .map(|candidates| get_max(candidates & !crust))
+                    .sum::<u32>() as i64;
+                // The filling holds distinct values, which limits the sum even further.
+                let length = filling.len() as i64;
+                let distinct_min = (2..2 + length).sum::<i64>();
+                let distinct_max = (SUBGRID_LENGTH as i64 - length..SUBGRID_LENGTH as i64).sum();
+                if sum < min_sum.max(distinct_min) || sum > max_sum.min(distinct_max) {
+                    continue;
+                }
+                for (index, candidates) in candidates.iter().enumerate() {
+                    allowed[index] |= if index == first {
+                        one
+                    } else if index == second {
+                        nine
+                    } else if index >= start && index < end {
+                        let (own_min, own_max) = (
+                            get_min(candidates & !crust) as i64,
+                            get_max(candidates & !crust) as i64,
+                        );
+                        let range = get_range(sum - (max_sum - own_max), sum - (min_sum - own_min));
+                        candidates & !crust & range
+                    } else {
+                        candidates & !crust
+                    };
+                }
+            }
+        }
+        for (candidates, allowed) in candidates.iter_mut().zip(allowed) {
+            *candidates &= allowed;
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParityKind {
+    Odd,
+    Even,
+}
+
+impl ParityKind {
+    pub const ALL: [ParityKind; 2] = [ParityKind::Odd, ParityKind::Even];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ParityKind::Odd => "odd",
+            ParityKind::Even => "even",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    pub fn get_candidates(&self) -> u16 {
+        let odd = (1..=SUBGRID_LENGTH)
+            .step_by(2)
+            .fold(0, |candidates, value| candidates | (1 << value));
+        match self {
+            ParityKind::Odd => odd,
+            ParityKind::Even => ALL_CANDIDATES & !odd,
+        }
+    }
+}
+
+// The value of the marked cell is odd, or even.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Parity {
+    pub kind: ParityKind,
+    pub cell: Cell,
+}
+
+impl Parity {
+    pub fn new(kind: ParityKind, cell: Cell) -> Self {
+        Self { kind, cell }
+    }
+}
+
+impl CellConstraint for Parity {
+    fn name(&self) -> &'static str {
+        match self.kind {
+            ParityKind::Odd => "odd cell",
+            ParityKind::Even => "even cell",
+        }
+    }
+
+    fn get_cells(&self) -> Vec<Cell> {
+        vec![self.cell]
+    }
+
+    fn restrict(&self, candidates: &mut [u16]) {
+        candidates[0] &= self.kind.get_candidates();
+    }
+}
+
+// The value of the smaller cell is less than the value of the greater cell, which is adjacent to
+// it. Chains of inequalities are tightened by restricting each of them until nothing changes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Inequality {
+    pub smaller: Cell,
+    pub greater: Cell,
+}
+
+impl Inequality {
+    pub fn new(smaller: Cell, greater: Cell) -> Self {
+        Self { smaller, greater }
+    }
+
+    pub fn connects(&self, first: &Cell, second: &Cell) -> bool {
+        (self.smaller == *first && self.greater == *second)
+            || (self.smaller == *second && self.greater == *first)
+    }
+}
+
+impl CellConstraint for Inequality {
+    fn name(&self) -> &'static str {
+        "inequality"
+    }
+
+    fn get_cells(&self) -> Vec<Cell> {
+        vec![self.smaller, self.greater]
+    }
+
+    fn restrict(&self, candidates: &mut [u16]) {
+        let (smaller, greater) = (candidates[0], candidates[1]);
+        if smaller == 0 || greater == 0 {
+            candidates.iter_mut().for_each(|candidates| *candidates = 0);
+            return;
+        }
+        candidates[0] &= get_range(1, get_max(greater) as i64 - 1);
+        candidates[1] &= get_range(get_min(smaller) as i64 + 1, SUBGRID_LENGTH as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_candidates(values: &[&[u32]]) -> Vec<u16> {
+        values
+            .iter()
+            .map(|values| {
+                values
+                    .iter()
+                    .fold(0, |candidates, value| candidates | 1 << value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_thermometer_values_increase() {
+        let cells = (0..3).map(|x| Cell::new(x, 0)).collect();
+        let thermometer = Thermometer::new(cells);
+        let mut candidates = vec![ALL_CANDIDATES; 3];
+        thermometer.restrict(&mut candidates);
+        let expected = to_candidates(&[
+            &[1, 2, 3, 4, 5, 6, 7],
+            &[2, 3, 4, 5, 6, 7, 8],
+            &[3, 4, 5, 6, 7, 8, 9],
+        ]);
+        assert_eq!(expected, candidates);
+        let mut candidates = to_candidates(&[&[4], &[2, 5, 9], &[5, 6]]);
+        thermometer.restrict(&mut candidates);
+        assert_eq!(to_candidates(&[&[4], &[5], &[6]]), candidates);
+        let mut candidates = to_candidates(&[&[4], &[3], &[6]]);
+        thermometer.restrict(&mut candidates);
+        assert!(candidates.contains(&0));
+    }
+
+    #[test]
+    fn test_arrow_circle_is_sum_of_cells() {
+        let arrow = Arrow::new(Cell::new(0, 0), vec![Cell::new(1, 0), Cell::new(2, 0)]);
+        let mut candidates = vec![ALL_CANDIDATES; 3];
+        arrow.restrict(&mut candidates);
+        assert_eq!(get_range(2, 9), candidates[0]);
+        assert_eq!(get_range(1, 8), candidates[1]);
+        let mut values = vec![0; GRID_JOINT_SIZE * GRID_JOINT_SIZE];
+        values[1] = 3;
+        assert!(arrow.allows(&values, 0, 9));
+        assert!(!arrow.allows(&values, 0, 3));
+        values[2] = 5;
+        assert!(arrow.is_feasible(&values));
+        assert!(arrow.allows(&values, 0, 8));
+        assert!(!arrow.allows(&values, 0, 9));
+        assert!(arrow.allows(&values, 9, 9));
+    }
+
+    #[test]
+    fn test_sandwich_sum_is_between_one_and_nine() {
+        let sandwich = Sandwich::new(Line::Row(0), 0);
+        let mut values = vec![0; GRID_JOINT_SIZE * GRID_JOINT_SIZE];
+        values[3] = 1;
+        assert!(sandwich.allows(&values, 4, 9));
+        assert!(sandwich.allows(&values, 2, 9));
+        assert!(!sandwich.allows(&values, 5, 9));
+        let sandwich = Sandwich::new(Line::Column(0), MAX_SANDWICH_SUM);
+        let mut candidates = vec![ALL_CANDIDATES; GRID_JOINT_SIZE];
+        sandwich.restrict(&mut candidates);
+        assert_eq!(get_range(1, 1) | get_range(9, 9), candidates[0]);
+        assert_eq!(get_range(2, 8), candidates[4]);
+        let solved = [2, 1, 3, 4, 9, 5, 6, 7, 8];
+        let mut candidates = solved.iter().map(|value| 1 << value).collect::<Vec<u16>>();
+        Sandwich::new(Line::Row(0), 7).restrict(&mut candidates);
+        assert!(candidates.iter().all(|candidates| *candidates != 0));
+        Sandwich::new(Line::Row(0), 8).restrict(&mut candidates);
+        assert!(candidates.iter().all(|candidates| *candidates == 0));
+    }
+
+    #[test]
+    fn test_parity_and_inequality_restrict_values() {
+        let parity = Parity::new(ParityKind::Even, Cell::new(0, 0));
+        let mut candidates = vec![ALL_CANDIDATES];
+        parity.restrict(&mut candidates);
+        assert_eq!(to_candidates(&[&[2, 4, 6, 8]]), candidates);
+        let mut values = vec![0; GRID_JOINT_SIZE * GRID_JOINT_SIZE];
+        values[0] = 3;
+        assert!(!parity.is_feasible(&values));
+        assert!(Parity::new(ParityKind::Odd, Cell::new(0, 0)).is_feasible(&values));
+        let inequality = Inequality::new(Cell::new(0, 0), Cell::new(1, 0));
+        let mut candidates = to_candidates(&[&[4, 5, 8], &[2, 3, 6, 9]]);
+        inequality.restrict(&mut candidates);
+        assert_eq!(to_candidates(&[&[4, 5, 8], &[6, 9]]), candidates);
+        let mut candidates = to_candidates(&[&[7, 9], &[1, 8]]);
+        inequality.restrict(&mut candidates);
+        assert_eq!(to_candidates(&[&[7], &[8]]), candidates);
+        let mut candidates = to_candidates(&[&[9], &[2]]);
+        inequality.restrict(&mut candidates);
+        assert_eq!(vec![0, 0], candidates);
+    }
+}
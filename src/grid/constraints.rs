@@ -0,0 +1,556 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::{
+    Arrow, CellConstraint, Inequality, Parity, Sandwich, Thermometer,
+};
+use crate::grid::visibility::{Relation, Visibility};
+use crate::grid::{GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_LENGTH, SUBGRID_SIZE};
+
+pub const MAX_CAGE_SUM: u32 = (SUBGRID_LENGTH * (SUBGRID_LENGTH + 1) / 2) as u32;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Constraints {
+    pub diagonal: bool,
+    pub windows: bool,
+    pub cages: Vec<Cage>,
+    pub regions: Option<Regions>,
+    pub relations: Vec<Relation>,
+    pub non_consecutive: bool,
+    pub dots: Vec<Dot>,
+    // With the negative constraint, adjacent cells without a dot are neither consecutive nor in
+    // a 1:2 ratio.
+    pub negative_dots: bool,
+    pub thermometers: Vec<Thermometer>,
+    pub arrows: Vec<Arrow>,
+    pub sandwiches: Vec<Sandwich>,
+    pub parities: Vec<Parity>,
+    pub inequalities: Vec<Inequality>,
+}
+
+impl Constraints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_classic(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn extra_units(&self) -> Vec<Unit> {
+        let mut units = Vec::new();
+        if self.diagonal {
+            let main = (0..GRID_JOINT_SIZE).map(|i| Cell::new(i, i)).collect();
+            units.push(Unit::new(UnitKind::Diagonal, 0, main));
+            let anti = (0..GRID_JOINT_SIZE)
+                .map(|i| Cell::new(GRID_JOINT_SIZE - 1 - i, i))
+                .collect();
+            units.push(Unit::new(UnitKind::Diagonal, 1, anti));
+        }
+        if self.windows {
+            let bands = get_window_bands();
+            let mut ghost_index = 0;
+            for (band_y, rows) in bands.iter().enumerate() {
+                for (band_x, columns) in bands.iter().enumerate() {
+                    let cells = rows
+                        .iter()
+                        .flat_map(|y| columns.iter().map(move |x| Cell::new(*x, *y)))
+                        .collect();
+                    if band_x < GRID_SIZE - 1 && band_y < GRID_SIZE - 1 {
+                        let index = band_y * (GRID_SIZE - 1) + band_x;
+                        units.push(Unit::new(UnitKind::Window, index, cells));
+                    } else {
+                        units.push(Unit::new(UnitKind::GhostWindow, ghost_index, cells));
+                        ghost_index += 1;
+                    }
+                }
+            }
+        }
+        for (index, cage) in self.cages.iter().enumerate() {
+            units.push(Unit::new(UnitKind::Cage, index, cage.cells.clone()));
+        }
+        if let Some(regions) = &self.regions {
+            for index in 0..SUBGRID_LENGTH {
+                units.push(Unit::new(UnitKind::Region, index, regions.get_cells(index)));
+            }
+        }
+        units
+    }
+
+    // Custom regions replace the subgrids, which then no longer need distinct digits.
+    pub fn has_subgrid_units(&self) -> bool {
+        self.regions.is_none()
+    }
+
+    pub fn get_region(&self, x_joint: usize, y_joint: usize) -> usize {
+        match &self.regions {
+            Some(regions) => regions.get_region(x_joint, y_joint),
+            None => Cell::new(x_joint, y_joint).subgrid(),
+        }
+    }
+
+    pub fn has_cell_constraints(&self) -> bool {
+        !self.cages.is_empty()
+            || !self.relations.is_empty()
+            || self.has_pair_constraints()
+            || self.get_cell_constraints().next().is_some()
+    }
+
+    pub fn get_cell_constraints(&self) -> impl Iterator<Item = &dyn CellConstraint> + '_ {
+        let thermometers = self
+            .thermometers
+            .iter()
+            .map(|thermometer| thermometer as &dyn CellConstraint);
+        let arrows = self.arrows.iter().map(|arrow| arrow as &dyn CellConstraint);
+        let sandwiches = self
+            .sandwiches
+            .iter()
+            .map(|sandwich| sandwich as &dyn CellConstraint);
+        let parities = self
+            .parities
+            .iter()
+            .map(|parity| parity as &dyn CellConstraint);
+        let inequalities = self
+            .inequalities
+            .iter()
+            .map(|inequality| inequality as &dyn CellConstraint);
+        thermometers
+            .chain(arrows)
+            .chain(sandwiches)
+            .chain(parities)
+            .chain(inequalities)
+    }
+
+    pub fn has_pair_constraints(&self) -> bool {
+        self.non_consecutive || !self.dots.is_empty() || self.negative_dots
+    }
+
+    pub fn get_dot(&self, first: &Cell, second: &Cell) -> Option<&Dot> {
+        self.dots.iter().find(|dot| dot.connects(first, second))
+    }
+
+    // Checks the constraints between two orthogonally adjacent cells.
+    pub fn allows_pair(
+        &self,
+        first: &Cell,
+        second: &Cell,
+        first_value: u32,
+        second_value: u32,
+    ) -> bool {
+        if self.non_consecutive && first_value.abs_diff(second_value) == 1 {
+            return false;
+        }
+        match self.get_dot(first, second) {
+            Some(dot) => dot.kind.allows(first_value, second_value),
+            None => {
+                !self.negative_dots
+                    || DotKind::ALL
+                        .iter()
+                        .all(|kind| !kind.allows(first_value, second_value))
+            }
+        }
+    }
+
+    pub fn get_visible_cells(&self, x_joint: usize, y_joint: usize) -> Vec<Cell> {
+        let mut cells = Vec::new();
+        for relation in &self.relations {
+            cells.extend(relation.get_visible_cells(x_joint, y_joint));
+        }
+        cells
+    }
+
+    pub fn get_cage(&self, x_joint: usize, y_joint: usize) -> Option<&Cage> {
+        self.cages
+            .iter()
+            .find(|cage| cage.contains(x_joint, y_joint))
+    }
+
+    // Checks the constraints that are not plain units, with `values` holding the placed values of
+    // all cells in row-major order and 0 for empty cells. Returns false only when placing `value` at
+    // `cell` certainly breaks a constraint.
+    pub fn allows(&self, values: &[u32], cell: usize, value: u32) -> bool {
+        let current = Cell::from_index(cell);
+        let (x, y) = (current.column(), current.row());
+        let is_visible = self
+            .get_visible_cells(x, y)
+            .iter()
+            .any(|other| values[other.index()] == value);
+        if is_visible {
+            return false;
+        }
+        if self.has_pair_constraints() {
+            for other in get_adjacent_cells(x, y) {
+                let other_value = values[other.index()];
+                if other_value != 0 && !self.allows_pair(&current, &other, value, other_value) {
+                    return false;
+                }
+            }
+        }
+        if let Some(cage) = self.get_cage(x, y) {
+            if !cage.allows(values, cell, value) {
+                return false;
+            }
+        }
+        self.get_cell_constraints()
+            .all(|constraint| constraint.allows(values, cell, value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UnitKind {
+    Row,
+    Column,
+    Subgrid,
+    Diagonal,
+    Cage,
+    Region,
+    Window,
+    GhostWindow,
+    Relation(Relation),
+}
+
+impl Display for UnitKind {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UnitKind::Row => "row",
+            UnitKind::Column => "column",
+            UnitKind::Subgrid => "subgrid",
+            UnitKind::Diagonal => "diagonal",
+            UnitKind::Cage => "cage",
+            UnitKind::Region => "region",
+            UnitKind::Window => "window",
+            UnitKind::GhostWindow => "ghost window",
+            UnitKind::Relation(relation) => relation.name(),
+        };
+        formatter.write_str(name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Unit {
+    pub kind: UnitKind,
+    pub index: usize,
+    pub cells: Vec<Cell>,
+}
+
+impl Unit {
+    pub fn new(kind: UnitKind, index: usize, cells: Vec<Cell>) -> Self {
+        Self { kind, index, cells }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.cells.len() == SUBGRID_LENGTH
+    }
+
+    pub fn contains(&self, x_joint: usize, y_joint: usize) -> bool {
+        self.cells.contains(&Cell::new(x_joint, y_joint))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DotKind {
+    White,
+    Black,
+}
+
+impl DotKind {
+    pub const ALL: [DotKind; 2] = [DotKind::White, DotKind::Black];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DotKind::White => "white",
+            DotKind::Black => "black",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|kind| kind.name() == name)
+    }
+
+    // White dots join consecutive digits and black dots join digits where one is double the other.
+    pub fn allows(&self, first_value: u32, second_value: u32) -> bool {
+        match self {
+            DotKind::White => first_value.abs_diff(second_value) == 1,
+            DotKind::Black => first_value == 2 * second_value || second_value == 2 * first_value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Dot {
+    pub kind: DotKind,
+    pub first: Cell,
+    pub second: Cell,
+}
+
+impl Dot {
+    pub fn new(kind: DotKind, first: Cell, second: Cell) -> Self {
+        Self {
+            kind,
+            first,
+            second,
+        }
+    }
+
+    pub fn connects(&self, first: &Cell, second: &Cell) -> bool {
+        (self.first == *first && self.second == *second)
+            || (self.first == *second && self.second == *first)
+    }
+}
+
+// Splits the rows, or the columns, into the bands of the windows, which lie one cell apart from
+// each other and from the edges, and a last band of the cells between them. The bands combined with
+// the last band form the ghost windows, which hold distinct digits as well.
+fn get_window_bands() -> Vec<Vec<usize>> {
+    let mut bands = (0..GRID_SIZE - 1)
+        .map(|band| {
+            let start = 1 + band * (SUBGRID_SIZE + 1);
+            (start..start + SUBGRID_SIZE).collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    bands.push((0..GRID_SIZE).map(|i| i * (SUBGRID_SIZE + 1)).collect());
+    bands
+}
+
+pub fn get_adjacent_cells(x_joint: usize, y_joint: usize) -> Vec<Cell> {
+    let mut cells = Vec::with_capacity(4);
+    if y_joint > 0 {
+        cells.push(Cell::new(x_joint, y_joint - 1));
+    }
+    if x_joint > 0 {
+        cells.push(Cell::new(x_joint - 1, y_joint));
+    }
+    if x_joint + 1 < GRID_JOINT_SIZE {
+        cells.push(Cell::new(x_joint + 1, y_joint));
+    }
+    if y_joint + 1 < GRID_JOINT_SIZE {
+        cells.push(Cell::new(x_joint, y_joint + 1));
+    }
+    cells
+}
+
+// The region of every cell, in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Regions {
+    pub cells: Vec<usize>,
+}
+
+impl Regions {
+    pub fn new(cells: Vec<usize>) -> Self {
+        Self { cells }
+    }
+
+    pub fn get_region(&self, x_joint: usize, y_joint: usize) -> usize {
+        self.cells[Cell::new(x_joint, y_joint).index()]
+    }
+
+    pub fn get_cells(&self, region: usize) -> Vec<Cell> {
+        (0..self.cells.len())
+            .filter(|cell| self.cells[*cell] == region)
+            .map(Cell::from_index)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Cage {
+    pub sum: u32,
+    pub cells: Vec<Cell>,
+}
+
+impl Cage {
+    pub fn new(sum: u32, cells: Vec<Cell>) -> Self {
+        Self { sum, cells }
+    }
+
+    pub fn contains(&self, x_joint: usize, y_joint: usize) -> bool {
+        self.cells.contains(&Cell::new(x_joint, y_joint))
+    }
+
+    // Sets of values, as bit sets where bit `n` stands for value `n`, that fill the cage and add up
+    // to its sum.
+    pub fn combinations(&self) -> Vec<u16> {
+        (0..1u16 << SUBGRID_LENGTH)
+            .map(|set| set << 1)
+            .filter(|set| set.count_ones() as usize == self.cells.len())
+            .filter(|set| {
+                let sum: u32 = (1..=SUBGRID_LENGTH as u32)
+                    .filter(|value| set & (1 << value) != 0)
+                    .sum();
+                sum == self.sum
+            })
+            .collect()
+    }
+
+    pub fn is_satisfied_by(&self, values: &[u32]) -> bool {
+        let (used, sum, empty) = self.get_state(values, None);
+        self.is_feasible(used, sum, empty)
+    }
+
+    pub fn allows(&self, values: &[u32], cell: usize, value: u32) -> bool {
+        let (used, sum, empty) = self.get_state(values, Some(cell));
+        used & (1 << value) == 0 && self.is_feasible(used | (1 << value), sum + value, empty)
+    }
+
+    fn get_state(&self, values: &[u32], skipped_cell: Option<usize>) -> (u16, u32, usize) {
+        let mut used = 0u16;
+        let mut sum = 0;
+        let mut empty = 0;
+        for cell in &self.cells {
+            let cell = cell.index();
+            if Some(cell) == skipped_cell {
+                continue;
+            }
+            match values[cell] {
+                0 => empty += 1,
+                value => {
+                    used |= 1 << value;
+                    sum += value;
+                }
+            }
+        }
+        (used, sum, empty)
+    }
+
+    // The remaining cells can only hold unused values, so their sum is bounded by the sums of the
+    // smallest and the largest of them.
+    fn is_feasible(&self, used: u16, sum: u32, empty: usize) -> bool {
+        if sum > self.sum {
+            return false;
+        }
+        let available = || (1..=SUBGRID_LENGTH as u32).filter(|value| used & (1 << value) == 0);
+        if available().count() < empty {
+            return false;
+        }
+        let remaining = self.sum - sum;
+        let min: u32 = available().take(empty).sum();
+        let max: u32 = available().rev().take(empty).sum();
+        min <= remaining && remaining <= max
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagonal_units_cover_both_diagonals() {
+        let mut constraints = Constraints::new();
+        constraints.diagonal = true;
+        assert!(!constraints.is_classic());
+        let units = constraints.extra_units();
+        assert_eq!(2, units.len());
+        assert!(units[0].contains(0, 0) && units[0].contains(8, 8));
+        assert!(units[1].contains(8, 0) && units[1].contains(0, 8));
+        assert!(units.iter().all(|unit| unit.contains(4, 4)));
+        assert!(!units.iter().any(|unit| unit.contains(1, 0)));
+        assert!(Constraints::new().extra_units().is_empty());
+    }
+
+    #[test]
+    fn test_windows_and_ghost_windows_cover_every_cell_once() {
+        let mut constraints = Constraints::new();
+        constraints.windows = true;
+        let units = constraints.extra_units();
+        let windows = units
+            .iter()
+            .filter(|unit| unit.kind == UnitKind::Window)
+            .collect::<Vec<_>>();
+        assert_eq!(4, windows.len());
+        assert!(windows[0].contains(1, 1) && windows[0].contains(3, 3));
+        assert!(windows[3].contains(5, 5) && windows[3].contains(7, 7));
+        assert_eq!(9, units.len());
+        assert!(units.iter().all(Unit::is_complete));
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                let count = units.iter().filter(|unit| unit.contains(x, y)).count();
+                assert_eq!(1, count);
+            }
+        }
+        let ghost = units.iter().find(|unit| unit.kind == UnitKind::GhostWindow);
+        assert!(ghost.unwrap().contains(0, 1) && ghost.unwrap().contains(8, 3));
+    }
+
+    #[test]
+    fn test_cage_combinations_add_up_to_sum() {
+        let cells = vec![Cell::new(0, 0), Cell::new(1, 0)];
+        let cage = Cage::new(4, cells.clone());
+        assert_eq!(vec![0b1010], cage.combinations());
+        let cage = Cage::new(10, cells);
+        assert_eq!(4, cage.combinations().len());
+        let cells = (0..SUBGRID_LENGTH).map(|x| Cell::new(x, 0)).collect();
+        assert_eq!(1, Cage::new(MAX_CAGE_SUM, cells).combinations().len());
+    }
+
+    #[test]
+    fn test_cage_rejects_values_that_cannot_reach_sum() {
+        let cage = Cage::new(6, vec![Cell::new(0, 0), Cell::new(1, 0), Cell::new(2, 0)]);
+        let mut values = vec![0; GRID_JOINT_SIZE * GRID_JOINT_SIZE];
+        assert!(cage.allows(&values, 0, 1));
+        assert!(cage.allows(&values, 0, 3));
+        assert!(!cage.allows(&values, 0, 4));
+        values[1] = 2;
+        assert!(!cage.allows(&values, 0, 2));
+        assert!(cage.allows(&values, 0, 3));
+        values[0] = 3;
+        assert!(!cage.allows(&values, 2, 4));
+        assert!(cage.allows(&values, 2, 1));
+        values[2] = 1;
+        assert!(cage.is_satisfied_by(&values));
+        values[2] = 4;
+        assert!(!cage.is_satisfied_by(&values));
+    }
+
+    #[test]
+    fn test_pairs_follow_dots_and_negative_constraint() {
+        let (first, second, third) = (Cell::new(0, 0), Cell::new(1, 0), Cell::new(0, 1));
+        let mut constraints = Constraints::new();
+        assert!(!constraints.has_pair_constraints());
+        constraints
+            .dots
+            .push(Dot::new(DotKind::White, first, second));
+        constraints
+            .dots
+            .push(Dot::new(DotKind::Black, third, first));
+        assert!(constraints.allows_pair(&second, &first, 5, 4));
+        assert!(!constraints.allows_pair(&first, &second, 5, 7));
+        assert!(constraints.allows_pair(&first, &third, 3, 6));
+        assert!(constraints.allows_pair(&first, &third, 6, 3));
+        assert!(!constraints.allows_pair(&first, &third, 3, 5));
+        let fourth = Cell::new(1, 1);
+        assert!(constraints.allows_pair(&second, &fourth, 2, 4));
+        constraints.negative_dots = true;
+        assert!(!constraints.allows_pair(&second, &fourth, 2, 4));
+        assert!(!constraints.allows_pair(&second, &fourth, 2, 3));
+        assert!(constraints.allows_pair(&second, &fourth, 2, 5));
+        constraints.non_consecutive = true;
+        assert!(!constraints.allows_pair(&first, &second, 5, 4));
+        assert_eq!(3, get_adjacent_cells(0, 1).len());
+        assert_eq!(4, get_adjacent_cells(4, 4).len());
+    }
+}
@@ -23,9 +23,12 @@
  */
 
 use std::borrow::Cow;
+use std::fmt::Write;
 
+use crate::grid::cell_constraint::CellConstraint;
 use crate::grid::digit::Digit;
-use crate::grid::{Grid, GRID_SIZE, SUBGRID_SIZE};
+use crate::grid::samurai::{Samurai, SAMURAI_SIZE};
+use crate::grid::{Grid, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_SIZE};
 use std::char::from_digit;
 
 pub type GeneratorResult = Result<String, GeneratorError>;
@@ -58,7 +61,7 @@ impl Generator {
                 result.push(' ');
                 result.push(' ');
                 for grid_x in 0..GRID_SIZE {
-                    let subgrid = grid.get_subgrid_absolute(grid_x, grid_y);
+                    let subgrid = grid.get_subgrid_view_absolute(grid_x, grid_y);
                     for subgrid_x in 0..SUBGRID_SIZE {
                         let digit = subgrid.get_digit(subgrid_x, subgrid_y);
                         let digit_char = digit.to_char();
@@ -74,9 +77,184 @@ impl Generator {
             }
             self.add_line_spacer(&mut result);
         }
+        result.push('\n');
+        self.add_declarations(grid, &mut result);
         Ok(result)
     }
 
+    pub fn generate_syntax(&self, grid: &Grid) -> GeneratorResult {
+        let mut result = String::new();
+        for y in 0..GRID_JOINT_SIZE {
+            if y > 0 && y % SUBGRID_SIZE == 0 {
+                result.push('\n');
+            }
+            for x in 0..GRID_JOINT_SIZE {
+                let digit = grid.get_digit(x, y);
+                result.push(digit.to_char());
+                if x % SUBGRID_SIZE == SUBGRID_SIZE - 1 {
+                    result.push(';');
+                    if x < GRID_JOINT_SIZE - 1 {
+                        result.push_str("  ");
+                    }
+                } else {
+                    result.push_str(", ");
+                }
+            }
+            result.push('\n');
+        }
+        if !grid.constraints.is_classic() {
+            result.push('\n');
+        }
+        self.add_declarations(grid, &mut result);
+        Ok(result)
+    }
+
+    pub fn generate_line(&self, grid: &Grid) -> GeneratorResult {
+        let mut result = String::with_capacity(GRID_JOINT_SIZE * GRID_JOINT_SIZE);
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                let digit = grid.get_digit(x, y);
+                let digit_char = match digit {
+                    Digit::Known(_) => digit.to_char(),
+                    Digit::Unknown(_) => '.',
+                };
+                result.push(digit_char);
+            }
+        }
+        for declaration in self.generate_declarations(grid) {
+            result.push('\n');
+            result.push_str(&declaration);
+        }
+        Ok(result)
+    }
+
+    pub fn generate_samurai(&self, samurai: &Samurai) -> GeneratorResult {
+        let mut result = String::with_capacity((SAMURAI_SIZE + 1) * SAMURAI_SIZE);
+        for y in 0..SAMURAI_SIZE {
+            let mut line = String::with_capacity(SAMURAI_SIZE);
+            for x in 0..SAMURAI_SIZE {
+                let digit_char = match samurai.get_digit(x, y) {
+                    Some(digit @ Digit::Known(_)) => digit.to_char(),
+                    Some(Digit::Unknown(_)) => '.',
+                    None => ' ',
+                };
+                line.push(digit_char);
+            }
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        Ok(result)
+    }
+
+    pub fn generate_declarations(&self, grid: &Grid) -> Vec<String> {
+        let mut declarations = Vec::new();
+        if grid.constraints.diagonal {
+            declarations.push("diagonal".to_owned());
+        }
+        if grid.constraints.windows {
+            declarations.push("windows".to_owned());
+        }
+        for relation in &grid.constraints.relations {
+            declarations.push(relation.name().to_owned());
+        }
+        if grid.constraints.non_consecutive {
+            declarations.push("non-consecutive".to_owned());
+        }
+        for dot in &grid.constraints.dots {
+            let (first, second) = (&dot.first, &dot.second);
+            declarations.push(format!(
+                "dot {} r{}c{} r{}c{}",
+                dot.kind.name(),
+                first.row() + 1,
+                first.column() + 1,
+                second.row() + 1,
+                second.column() + 1
+            ));
+        }
+        if grid.constraints.negative_dots {
+            declarations.push("negative-dots".to_owned());
+        }
+        for cage in &grid.constraints.cages {
+            let mut declaration = format!("cage {}", cage.sum);
+            for cell in &cage.cells {
+                write!(declaration, " r{}c{}", cell.row() + 1, cell.column() + 1).unwrap();
+            }
+            declarations.push(declaration);
+        }
+        // Thermometers and arrows are declared as paths, in the order of their cells.
+        let thermometers = grid.constraints.thermometers.iter();
+        let arrows = grid.constraints.arrows.iter();
+        let paths = thermometers
+            .map(|thermometer| thermometer as &dyn CellConstraint)
+            .chain(arrows.map(|arrow| arrow as &dyn CellConstraint));
+        for path in paths {
+            let mut declaration = path.name().to_owned();
+            for cell in path.get_cells() {
+                write!(declaration, " r{}c{}", cell.row() + 1, cell.column() + 1).unwrap();
+            }
+            declarations.push(declaration);
+        }
+        for sandwich in &grid.constraints.sandwiches {
+            let line = sandwich.line;
+            declarations.push(format!(
+                "sandwich {} {} {}",
+                line.name(),
+                line.index() + 1,
+                sandwich.sum
+            ));
+        }
+        // Markers of the same kind that follow each other share a declaration.
+        let mut parities = grid.constraints.parities.iter().peekable();
+        while let Some(parity) = parities.next() {
+            let mut declaration = parity.kind.name().to_owned();
+            write!(
+                declaration,
+                " r{}c{}",
+                parity.cell.row() + 1,
+                parity.cell.column() + 1
+            )
+            .unwrap();
+            while let Some(next) = parities.next_if(|next| next.kind == parity.kind) {
+                write!(
+                    declaration,
+                    " r{}c{}",
+                    next.cell.row() + 1,
+                    next.cell.column() + 1
+                )
+                .unwrap();
+            }
+            declarations.push(declaration);
+        }
+        for inequality in &grid.constraints.inequalities {
+            let (smaller, greater) = (&inequality.smaller, &inequality.greater);
+            declarations.push(format!(
+                "inequality r{}c{} < r{}c{}",
+                smaller.row() + 1,
+                smaller.column() + 1,
+                greater.row() + 1,
+                greater.column() + 1
+            ));
+        }
+        if let Some(regions) = &grid.constraints.regions {
+            let mut declaration = "regions".to_owned();
+            for (cell, region) in regions.cells.iter().enumerate() {
+                if cell % GRID_JOINT_SIZE == 0 {
+                    declaration.push('\n');
+                }
+                declaration.push((b'A' + *region as u8) as char);
+            }
+            declarations.push(declaration);
+        }
+        declarations
+    }
+
+    fn add_declarations(&self, grid: &Grid, result: &mut String) {
+        for declaration in self.generate_declarations(grid) {
+            result.push_str(&declaration);
+            result.push('\n');
+        }
+    }
+
     fn add_upper_line_spacer(&self, result: &mut String) {
         let line_spacer = "_".repeat(Self::SPACER_LENGTH);
         result.push('.');
@@ -121,3 +299,96 @@ impl Digit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::cell::Cell;
+    use crate::grid::cell_constraint::{
+        Arrow, Inequality, Line, Parity, ParityKind, Sandwich, Thermometer,
+    };
+    use crate::grid::constraints::{Cage, Dot, DotKind, Regions};
+    use crate::grid::parser::Parser;
+    use crate::grid::visibility::Relation;
+
+    use super::*;
+
+    type TestResult = Result<(), GeneratorError>;
+
+    #[test]
+    fn test_syntax_is_generated_correctly() -> TestResult {
+        let syntax = include_str!("test.grid");
+        let grid = Parser::new().parse(syntax.to_owned())?;
+        let actual = Generator::new().generate_syntax(&grid)?;
+        assert_eq!(syntax.trim_end(), actual.trim_end());
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_is_generated_correctly() -> TestResult {
+        let grid = Parser::new().parse(include_str!("test.grid").to_owned())?;
+        let expected = "1239871..456654...789321..99871239..654456...321789..1\
+                        ...........................";
+        let actual = Generator::new().generate_line(&grid)?;
+        assert_eq!(expected, actual);
+        let parsed = Parser::new().parse_line(actual)?;
+        assert_eq!(grid, parsed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_declarations_are_generated() -> TestResult {
+        let mut grid = Parser::new().parse(include_str!("test.grid").to_owned())?;
+        grid.constraints.diagonal = true;
+        grid.constraints.windows = true;
+        grid.constraints.relations.push(Relation::AntiKnight);
+        grid.constraints.non_consecutive = true;
+        let (first, second) = (Cell::new(0, 0), Cell::new(0, 1));
+        grid.constraints
+            .dots
+            .push(Dot::new(DotKind::Black, first, second));
+        grid.constraints.negative_dots = true;
+        let cells = vec![Cell::new(5, 5), Cell::new(6, 6)];
+        grid.constraints.thermometers.push(Thermometer::new(cells));
+        let cells = vec![Cell::new(7, 7)];
+        let arrow = Arrow::new(Cell::new(8, 8), cells);
+        grid.constraints.arrows.push(arrow);
+        let sandwich = Sandwich::new(Line::Column(2), 12);
+        grid.constraints.sandwiches.push(sandwich);
+        for (kind, x) in [
+            (ParityKind::Odd, 0),
+            (ParityKind::Odd, 2),
+            (ParityKind::Even, 4),
+        ] {
+            let parity = Parity::new(kind, Cell::new(x, 3));
+            grid.constraints.parities.push(parity);
+        }
+        let (smaller, greater) = (Cell::new(1, 5), Cell::new(0, 5));
+        let inequality = Inequality::new(smaller, greater);
+        grid.constraints.inequalities.push(inequality);
+        let cells = vec![Cell::new(3, 0), Cell::new(3, 1)];
+        grid.constraints.cages.push(Cage::new(7, cells));
+        let regions = (0..GRID_JOINT_SIZE * GRID_JOINT_SIZE)
+            .map(|cell| cell / GRID_JOINT_SIZE)
+            .collect();
+        grid.constraints.regions = Some(Regions::new(regions));
+        let generator = Generator::new();
+        for syntax in [
+            generator.generate_syntax(&grid)?,
+            generator.generate_line(&grid)?,
+        ] {
+            assert!(syntax.contains(
+                "\ndiagonal\nwindows\nanti-knight\nnon-consecutive\ndot black r1c1 r2c1\nnegative-dots\ncage 7 r1c4 r2c4\nthermometer r6c6 r7c7\narrow r9c9 r8c8\nsandwich column 3 12\nodd r4c1 r4c3\neven r4c5\ninequality r6c2 < r6c1\nregions\nAAAAAAAAA\nBBBBBBBBB\n"
+            ));
+            assert!(syntax.trim_end().ends_with("\nIIIIIIIII"));
+        }
+        assert_eq!(
+            grid,
+            Parser::new().parse(generator.generate_syntax(&grid)?)?
+        );
+        assert_eq!(
+            grid,
+            Parser::new().parse_line(generator.generate_line(&grid)?)?
+        );
+        Ok(())
+    }
+}
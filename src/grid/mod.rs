@@ -22,161 +22,306 @@
  * SOFTWARE.
  */
 
+use crate::grid::canonical::find_canonical_transformation;
+use crate::grid::cell::Cell;
+use crate::grid::constraints::{Constraints, Unit, UnitKind};
 use crate::grid::digit::{Digit, LocalizedDigit};
 use crate::grid::position::Position;
+use crate::grid::units::Units;
+use crate::grid::validator::{ValidationReport, Validator};
 
+pub mod canonical;
+pub mod cell;
+pub mod cell_constraint;
+pub mod constraints;
 pub mod digit;
 pub mod generator;
 pub mod parser;
 pub mod position;
+pub mod renderer;
+pub mod samurai;
+pub mod svg;
+pub mod transform;
+pub mod units;
+pub mod validator;
+pub mod visibility;
 
 pub const GRID_SIZE: usize = 3;
 pub const GRID_LENGTH: usize = GRID_SIZE * GRID_SIZE;
 pub const GRID_JOINT_SIZE: usize = GRID_SIZE * SUBGRID_SIZE;
+pub const GRID_CELLS: usize = GRID_JOINT_SIZE * GRID_JOINT_SIZE;
 
 pub const SUBGRID_SIZE: usize = 3;
 pub const SUBGRID_LENGTH: usize = SUBGRID_SIZE * SUBGRID_SIZE;
 
+// The digits are stored in row-major order, so that the index of a cell addresses its digit.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Grid {
-    pub subgrids: Vec<Subgrid>,
+    pub digits: Vec<Digit>,
+    pub constraints: Constraints,
 }
 
 impl Default for Grid {
     fn default() -> Self {
-        let subgrids = vec![Subgrid::default(); GRID_LENGTH];
-        Self::new(subgrids)
+        let digits = vec![Digit::default(); GRID_CELLS];
+        Self::from_cells(digits, Constraints::default())
     }
 }
 
 impl Grid {
     pub fn new(subgrids: Vec<Subgrid>) -> Self {
-        Self { subgrids }
+        Self::with_constraints(subgrids, Constraints::default())
     }
 
-    pub fn is_solved(&self) -> bool {
-        for subgrid in &self.subgrids {
-            if !subgrid.is_solved() {
-                return false;
+    pub fn with_constraints(subgrids: Vec<Subgrid>, constraints: Constraints) -> Self {
+        let mut digits = vec![Digit::default(); GRID_CELLS];
+        for (index, subgrid) in subgrids.into_iter().enumerate() {
+            for (offset, digit) in subgrid.digits.into_iter().enumerate() {
+                digits[Cell::from_subgrid(index, offset).index()] = digit;
             }
         }
-        true
+        Self::from_cells(digits, constraints)
+    }
+
+    pub fn from_cells(digits: Vec<Digit>, constraints: Constraints) -> Self {
+        assert_eq!(
+            GRID_CELLS,
+            digits.len(),
+            "grid should have {} cells",
+            GRID_CELLS
+        );
+        Self {
+            digits,
+            constraints,
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.digits
+            .iter()
+            .all(|digit| matches!(digit, Digit::Known(_)))
     }
 
     pub fn is_solved_correctly(&self) -> bool {
+        let units = self.get_units();
         for x in 0..GRID_JOINT_SIZE {
             for y in 0..GRID_JOINT_SIZE {
                 let digit = self.get_digit(x, y);
-                let neighbours = self.get_neighbour_digits(x, y);
+                let neighbours = self.get_unit_neighbour_digits(&units, x, y);
                 if neighbours.contains(&digit) {
                     return false;
                 }
             }
         }
-        true
+        let report = self.validate();
+        report.unsatisfied_cages.is_empty()
+            && report.broken_pairs.is_empty()
+            && report.unsatisfied_constraints.is_empty()
+    }
+
+    pub fn validate(&self) -> ValidationReport {
+        let validator = Validator::new(self);
+        validator.validate()
+    }
+
+    // The representative of the puzzles equivalent to this one under the symmetries of Sudoku,
+    // holding only its known digits. Grids with variant constraints only represent themselves.
+    pub fn get_canonical_form(&self) -> Grid {
+        let digits = self.get_values().into_iter().map(Digit::from).collect();
+        let grid = Grid::from_cells(digits, self.constraints.clone());
+        find_canonical_transformation(&grid)
+            .and_then(|transformation| transformation.apply(&grid))
+            .unwrap_or(grid)
     }
 
+    pub fn is_equivalent(&self, other: &Grid) -> bool {
+        self.get_canonical_form() == other.get_canonical_form()
+    }
+
+    // All units whose cells hold distinct digits: the rows, the columns, the subgrids unless custom
+    // regions replace them, and the extra units of the variant constraints.
+    pub fn get_units(&self) -> Units {
+        let mut units = Vec::new();
+        for y in 0..GRID_JOINT_SIZE {
+            let cells = Cell::row_cells(y).collect();
+            units.push(Unit::new(UnitKind::Row, y, cells));
+        }
+        for x in 0..GRID_JOINT_SIZE {
+            let cells = Cell::column_cells(x).collect();
+            units.push(Unit::new(UnitKind::Column, x, cells));
+        }
+        if self.constraints.has_subgrid_units() {
+            for index in 0..GRID_LENGTH {
+                let cells = Cell::subgrid_cells(index).collect();
+                units.push(Unit::new(UnitKind::Subgrid, index, cells));
+            }
+        }
+        units.extend(self.constraints.extra_units());
+        Units::new(units)
+    }
+
+    // Builds the units on every call, so callers looking at many cells should build them once
+    // and use `get_unit_neighbour_digits` instead.
     pub fn get_neighbour_digits(&self, x_joint: usize, y_joint: usize) -> Vec<&Digit> {
-        let subgrid = self.get_subgrid(x_joint, y_joint);
-        let (subgrid_x, subgrid_y) = to_subgrid_coordinates(x_joint, y_joint);
-        let mut neighbours = subgrid.get_neighbour_digits(subgrid_x, subgrid_y);
-        let (grid_x, grid_y) = to_grid_coordinates(x_joint, y_joint);
-        let vertical_skip = grid_y * SUBGRID_SIZE;
-        let mut vertical_upper = (0..vertical_skip)
-            .map(|y_joint| self.get_digit(x_joint, y_joint))
-            .collect::<Vec<_>>();
-        let mut vertical_lower = (vertical_skip + SUBGRID_SIZE..GRID_JOINT_SIZE)
-            .map(|y_joint| self.get_digit(x_joint, y_joint))
-            .collect::<Vec<_>>();
-        let horizontal_skip = grid_x * SUBGRID_SIZE;
-        let mut horizontal_upper = (0..horizontal_skip)
-            .map(|x_joint| self.get_digit(x_joint, y_joint))
-            .collect::<Vec<_>>();
-        let mut horizontal_lower = (horizontal_skip + SUBGRID_SIZE..GRID_JOINT_SIZE)
-            .map(|x_joint| self.get_digit(x_joint, y_joint))
+        self.get_unit_neighbour_digits(&self.get_units(), x_joint, y_joint)
+    }
+
+    // Digits of the cells sharing a unit with the cell or seeing it through a relation, for
+    // callers that look at many cells with the same units.
+    pub fn get_unit_neighbour_digits(
+        &self,
+        units: &Units,
+        x_joint: usize,
+        y_joint: usize,
+    ) -> Vec<&Digit> {
+        let mut neighbours = units
+            .get_peers(x_joint, y_joint)
+            .iter()
+            .map(|cell| self.get_cell(*cell))
             .collect::<Vec<_>>();
-        neighbours.append(&mut vertical_upper);
-        neighbours.append(&mut vertical_lower);
-        neighbours.append(&mut horizontal_upper);
-        neighbours.append(&mut horizontal_lower);
+        for cell in self.constraints.get_visible_cells(x_joint, y_joint) {
+            neighbours.push(self.get_cell(cell));
+        }
         neighbours
     }
 
     pub fn get_vertical_digits(&self, x_joint: usize) -> Vec<&Digit> {
-        (0..GRID_JOINT_SIZE)
-            .map(|y_joint| self.get_digit(x_joint, y_joint))
-            .collect()
+        self.column(x_joint).collect()
     }
 
-    pub fn get_vertical_localized_digits(&self, x_joint: usize) -> Vec<LocalizedDigit> {
-        (0..GRID_JOINT_SIZE)
-            .map(|y_joint| {
-                let digit = self.get_digit(x_joint, y_joint);
-                LocalizedDigit::from_borrowed(digit, x_joint, y_joint)
-            })
+    pub fn get_vertical_localized_digits(&self, x_joint: usize) -> Vec<LocalizedDigit<'_>> {
+        Cell::column_cells(x_joint)
+            .map(|cell| self.get_localized_digit(cell))
             .collect()
     }
 
     pub fn get_horizontal_digits(&self, y_joint: usize) -> Vec<&Digit> {
-        (0..GRID_JOINT_SIZE)
-            .map(|x_joint| self.get_digit(x_joint, y_joint))
+        self.row(y_joint).collect()
+    }
+
+    pub fn get_horizontal_localized_digits(&self, y_joint: usize) -> Vec<LocalizedDigit<'_>> {
+        Cell::row_cells(y_joint)
+            .map(|cell| self.get_localized_digit(cell))
             .collect()
     }
 
-    pub fn get_horizontal_localized_digits(&self, y_joint: usize) -> Vec<LocalizedDigit> {
-        (0..GRID_JOINT_SIZE)
-            .map(|x_joint| {
-                let digit = self.get_digit(x_joint, y_joint);
-                LocalizedDigit::from_borrowed(digit, x_joint, y_joint)
+    pub fn get_values(&self) -> Vec<u32> {
+        self.digits
+            .iter()
+            .map(|digit| match digit {
+                Digit::Known(value) => *value,
+                Digit::Unknown(_) => 0,
             })
             .collect()
     }
 
+    pub fn row(&self, y_joint: usize) -> impl Iterator<Item = &Digit> + '_ {
+        Cell::row_cells(y_joint).map(move |cell| self.get_cell(cell))
+    }
+
+    pub fn column(&self, x_joint: usize) -> impl Iterator<Item = &Digit> + '_ {
+        Cell::column_cells(x_joint).map(move |cell| self.get_cell(cell))
+    }
+
+    pub fn subgrid(&self, index: usize) -> impl Iterator<Item = &Digit> + '_ {
+        Cell::subgrid_cells(index).map(move |cell| self.get_cell(cell))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = &Digit> + '_> + '_ {
+        (0..GRID_JOINT_SIZE).map(move |y_joint| self.row(y_joint))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &Digit> + '_> + '_ {
+        (0..GRID_JOINT_SIZE).map(move |x_joint| self.column(x_joint))
+    }
+
+    pub fn subgrids(&self) -> impl Iterator<Item = impl Iterator<Item = &Digit> + '_> + '_ {
+        (0..GRID_LENGTH).map(move |index| self.subgrid(index))
+    }
+
+    pub fn get_cell(&self, cell: Cell) -> &Digit {
+        &self.digits[cell.index()]
+    }
+
+    pub fn get_cell_mut(&mut self, cell: Cell) -> &mut Digit {
+        &mut self.digits[cell.index()]
+    }
+
+    pub fn set_cell(&mut self, cell: Cell, digit: Digit) {
+        self.digits[cell.index()] = digit;
+    }
+
     pub fn get_digit(&self, x_joint: usize, y_joint: usize) -> &Digit {
-        let subgrid = self.get_subgrid(x_joint, y_joint);
-        let (subgrid_x, subgrid_y) = to_subgrid_coordinates(x_joint, y_joint);
-        subgrid.get_digit(subgrid_x, subgrid_y)
+        self.get_cell(Cell::new(x_joint, y_joint))
     }
 
     pub fn get_digit_mut(&mut self, x_joint: usize, y_joint: usize) -> &mut Digit {
-        let subgrid = self.get_subgrid_mut(x_joint, y_joint);
-        let (subgrid_x, subgrid_y) = to_subgrid_coordinates(x_joint, y_joint);
-        subgrid.get_digit_mut(subgrid_x, subgrid_y)
+        self.get_cell_mut(Cell::new(x_joint, y_joint))
     }
 
     pub fn set_digit(&mut self, x_joint: usize, y_joint: usize, digit: Digit) {
-        let subgrid = self.get_subgrid_mut(x_joint, y_joint);
-        let (subgrid_x, subgrid_y) = to_subgrid_coordinates(x_joint, y_joint);
-        subgrid.set_digit(subgrid_x, subgrid_y, digit);
+        self.set_cell(Cell::new(x_joint, y_joint), digit);
     }
 
-    pub fn get_subgrid(&self, x_joint: usize, y_joint: usize) -> &Subgrid {
+    pub fn get_subgrid_view(&self, x_joint: usize, y_joint: usize) -> SubgridView<'_> {
         let (grid_x, grid_y) = to_grid_coordinates(x_joint, y_joint);
-        self.get_subgrid_absolute(grid_x, grid_y)
+        self.get_subgrid_view_absolute(grid_x, grid_y)
     }
 
-    pub fn get_subgrid_mut(&mut self, x_joint: usize, y_joint: usize) -> &mut Subgrid {
+    pub fn get_subgrid_view_mut(&mut self, x_joint: usize, y_joint: usize) -> SubgridViewMut<'_> {
         let (grid_x, grid_y) = to_grid_coordinates(x_joint, y_joint);
-        self.get_subgrid_absolute_mut(grid_x, grid_y)
+        self.get_subgrid_view_absolute_mut(grid_x, grid_y)
     }
 
-    pub fn get_subgrid_absolute(&self, x: usize, y: usize) -> &Subgrid {
+    pub fn get_subgrid_view_absolute(&self, x: usize, y: usize) -> SubgridView<'_> {
         validate_x(x, GRID_SIZE);
         validate_y(y, GRID_SIZE);
-        let index = self.to_grid_index(x, y);
-        self.subgrids.get(index).unwrap()
+        SubgridView::new(&self.digits, self.to_grid_index(x, y))
     }
 
-    pub fn get_subgrid_absolute_mut(&mut self, x: usize, y: usize) -> &mut Subgrid {
+    pub fn get_subgrid_view_absolute_mut(&mut self, x: usize, y: usize) -> SubgridViewMut<'_> {
         validate_x(x, GRID_SIZE);
         validate_y(y, GRID_SIZE);
         let index = self.to_grid_index(x, y);
-        self.subgrids.get_mut(index).unwrap()
+        SubgridViewMut::new(&mut self.digits, index)
+    }
+
+    // Copies of the subgrids in reading order, for callers of the former `subgrids` field.
+    pub fn to_subgrids(&self) -> Vec<Subgrid> {
+        (0..GRID_LENGTH)
+            .map(|index| Subgrid::new(self.subgrid(index).cloned().collect()))
+            .collect()
+    }
+
+    // The subgrid accessors below used to borrow stored subgrids. The digits are stored by cell
+    // now, so they return views with the same methods.
+    #[deprecated(note = "use `get_subgrid_view` instead")]
+    pub fn get_subgrid(&self, x_joint: usize, y_joint: usize) -> SubgridView<'_> {
+        self.get_subgrid_view(x_joint, y_joint)
+    }
+
+    #[deprecated(note = "use `get_subgrid_view_mut` instead")]
+    pub fn get_subgrid_mut(&mut self, x_joint: usize, y_joint: usize) -> SubgridViewMut<'_> {
+        self.get_subgrid_view_mut(x_joint, y_joint)
+    }
+
+    #[deprecated(note = "use `get_subgrid_view_absolute` instead")]
+    pub fn get_subgrid_absolute(&self, x: usize, y: usize) -> SubgridView<'_> {
+        self.get_subgrid_view_absolute(x, y)
+    }
+
+    #[deprecated(note = "use `get_subgrid_view_absolute_mut` instead")]
+    pub fn get_subgrid_absolute_mut(&mut self, x: usize, y: usize) -> SubgridViewMut<'_> {
+        self.get_subgrid_view_absolute_mut(x, y)
     }
 
     fn to_grid_index(&self, x: usize, y: usize) -> usize {
         x + y * GRID_SIZE
     }
+
+    fn get_localized_digit(&self, cell: Cell) -> LocalizedDigit<'_> {
+        LocalizedDigit::from_borrowed(self.get_cell(cell), cell.column(), cell.row())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
@@ -257,7 +402,7 @@ impl Subgrid {
         self.digits.iter().collect()
     }
 
-    pub fn localized_digits(&self) -> Vec<LocalizedDigit> {
+    pub fn localized_digits(&self) -> Vec<LocalizedDigit<'_>> {
         self.digits
             .iter()
             .enumerate()
@@ -277,6 +422,102 @@ impl Subgrid {
     }
 }
 
+// Subgrids are not stored, so they are borrowed as views of the grid digits, addressed with
+// coordinates local to the subgrid.
+#[derive(Debug, Clone, Copy)]
+pub struct SubgridView<'a> {
+    digits: &'a [Digit],
+    index: usize,
+}
+
+impl<'a> SubgridView<'a> {
+    fn new(digits: &'a [Digit], index: usize) -> Self {
+        Self { digits, index }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.digits()
+            .into_iter()
+            .all(|digit| matches!(digit, Digit::Known(_)))
+    }
+
+    pub fn get_digits_excluding(&self, positions: Vec<Position>) -> Vec<&'a Digit> {
+        (0..SUBGRID_LENGTH)
+            .filter(|offset| !positions.contains(&Position::from_index(*offset)))
+            .map(|offset| self.get_offset_digit(offset))
+            .collect()
+    }
+
+    pub fn get_neighbour_digits(&self, x: usize, y: usize) -> Vec<&'a Digit> {
+        let mut neighbours = self.digits();
+        neighbours.swap_remove(to_subgrid_offset(x, y));
+        neighbours
+    }
+
+    pub fn get_digit(&self, x: usize, y: usize) -> &'a Digit {
+        self.get_offset_digit(to_subgrid_offset(x, y))
+    }
+
+    pub fn digits(&self) -> Vec<&'a Digit> {
+        (0..SUBGRID_LENGTH)
+            .map(|offset| self.get_offset_digit(offset))
+            .collect()
+    }
+
+    pub fn localized_digits(&self) -> Vec<LocalizedDigit<'a>> {
+        (0..SUBGRID_LENGTH)
+            .map(|offset| {
+                let digit = self.get_offset_digit(offset);
+                LocalizedDigit::from_borrowed(digit, offset % SUBGRID_SIZE, offset / SUBGRID_SIZE)
+            })
+            .collect()
+    }
+
+    pub fn to_subgrid(&self) -> Subgrid {
+        Subgrid::new(self.digits().into_iter().cloned().collect())
+    }
+
+    fn get_offset_digit(&self, offset: usize) -> &'a Digit {
+        &self.digits[Cell::from_subgrid(self.index, offset).index()]
+    }
+}
+
+#[derive(Debug)]
+pub struct SubgridViewMut<'a> {
+    digits: &'a mut [Digit],
+    index: usize,
+}
+
+impl<'a> SubgridViewMut<'a> {
+    fn new(digits: &'a mut [Digit], index: usize) -> Self {
+        Self { digits, index }
+    }
+
+    pub fn as_view(&self) -> SubgridView<'_> {
+        SubgridView::new(self.digits, self.index)
+    }
+
+    pub fn get_digit(&self, x: usize, y: usize) -> &Digit {
+        &self.digits[self.to_cell(x, y).index()]
+    }
+
+    pub fn get_digit_mut(&mut self, x: usize, y: usize) -> &mut Digit {
+        &mut self.digits[self.to_cell(x, y).index()]
+    }
+
+    pub fn set_digit(&mut self, x: usize, y: usize, digit: Digit) {
+        *self.get_digit_mut(x, y) = digit;
+    }
+
+    fn to_cell(&self, x: usize, y: usize) -> Cell {
+        Cell::from_subgrid(self.index, to_subgrid_offset(x, y))
+    }
+}
+
 fn validate_x(x: usize, max_size: usize) {
     assert!(
         (0..max_size).contains(&x),
@@ -297,8 +538,10 @@ fn to_grid_coordinates(x: usize, y: usize) -> (usize, usize) {
     (x / SUBGRID_SIZE, y / SUBGRID_SIZE)
 }
 
-fn to_subgrid_coordinates(x: usize, y: usize) -> (usize, usize) {
-    (x % GRID_SIZE, y % GRID_SIZE)
+fn to_subgrid_offset(x: usize, y: usize) -> usize {
+    validate_x(x, SUBGRID_SIZE);
+    validate_y(y, SUBGRID_SIZE);
+    x + y * SUBGRID_SIZE
 }
 
 #[cfg(test)]
@@ -443,6 +686,77 @@ mod tests {
         assert_eq!(expected, grid);
     }
 
+    #[test]
+    fn test_grid_digits_are_stored_in_row_major_order() {
+        let grid = create_grid();
+        let values = grid.get_values();
+        assert_eq!(vec![1, 2, 3, 9, 8, 7, 1, 0, 0], values[..GRID_JOINT_SIZE]);
+        assert_eq!(5, values[Cell::new(4, 4).index()]);
+        assert_eq!(&Digit::Known(5), grid.get_cell(Cell::new(4, 4)));
+    }
+
+    #[test]
+    fn test_subgrid_views_write_through_to_the_grid() {
+        let mut grid = create_grid();
+        let subgrid = grid.get_subgrid_view(4, 4);
+        assert_eq!(4, subgrid.index());
+        assert_eq!(&Digit::Known(5), subgrid.get_digit(1, 1));
+        assert_eq!(
+            grid.subgrid(4).cloned().collect::<Vec<_>>(),
+            subgrid.to_subgrid().digits
+        );
+        let mut subgrid = grid.get_subgrid_view_absolute_mut(2, 0);
+        subgrid.set_digit(1, 1, Digit::Known(4));
+        *subgrid.get_digit_mut(2, 2) = Digit::Known(6);
+        assert_eq!(&Digit::Known(4), subgrid.as_view().get_digit(1, 1));
+        assert_eq!(&Digit::Known(4), grid.get_digit(7, 1));
+        assert_eq!(&Digit::Known(6), grid.get_digit(8, 2));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_former_subgrid_accessors_still_work() {
+        let mut grid = create_grid();
+        let subgrids = grid.to_subgrids();
+        assert_eq!(GRID_LENGTH, subgrids.len());
+        assert_eq!(Grid::new(subgrids.clone()).digits, grid.digits);
+        assert_eq!(subgrids[4], grid.get_subgrid(4, 4).to_subgrid());
+        assert_eq!(subgrids[5], grid.get_subgrid_absolute(2, 1).to_subgrid());
+        grid.get_subgrid_mut(8, 8).set_digit(0, 0, Digit::Known(3));
+        grid.get_subgrid_absolute_mut(0, 2)
+            .set_digit(2, 2, Digit::Known(7));
+        assert_eq!(&Digit::Known(3), grid.get_digit(6, 6));
+        assert_eq!(&Digit::Known(7), grid.get_digit(2, 8));
+    }
+
+    #[test]
+    fn test_grid_iterates_rows_columns_and_subgrids() {
+        let grid = create_grid();
+        assert_eq!(
+            grid.get_horizontal_digits(5),
+            grid.row(5).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            grid.get_vertical_digits(3),
+            grid.column(3).collect::<Vec<_>>()
+        );
+        let subgrid = grid.get_subgrid_view_absolute(2, 1);
+        assert_eq!(subgrid.digits(), grid.subgrid(5).collect::<Vec<_>>());
+        assert_eq!(subgrid.digits(), grid.get_subgrid_view(7, 4).digits());
+        assert_eq!(GRID_JOINT_SIZE, grid.rows().count());
+        assert_eq!(GRID_JOINT_SIZE, grid.columns().count());
+        assert_eq!(GRID_LENGTH, grid.subgrids().count());
+        let known = grid
+            .subgrids()
+            .map(|digits| {
+                digits
+                    .filter(|digit| matches!(digit, Digit::Known(_)))
+                    .count()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![9, 9, 2, 9, 9, 2, 0, 0, 1], known);
+    }
+
     #[test]
     fn test_subgrid_localized_digits() {
         let subgrid = Subgrid::from_digits(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
@@ -25,8 +25,17 @@
 use std::borrow::Cow;
 use std::str::Chars;
 
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::{
+    is_path, Arrow, Inequality, Line, Parity, ParityKind, Sandwich, Thermometer, MAX_SANDWICH_SUM,
+};
+use crate::grid::constraints::{
+    get_adjacent_cells, Cage, Constraints, Dot, DotKind, Regions, MAX_CAGE_SUM,
+};
 use crate::grid::digit::Digit;
-use crate::grid::{Grid, Subgrid, GRID_JOINT_SIZE, GRID_LENGTH, GRID_SIZE, SUBGRID_SIZE};
+use crate::grid::samurai::{Samurai, SAMURAI_SIZE};
+use crate::grid::visibility::Relation;
+use crate::grid::{Grid, GRID_CELLS, GRID_JOINT_SIZE, SUBGRID_LENGTH};
 
 pub type ParserResult = Result<Grid, ParserError>;
 pub type ParserError = Cow<'static, str>;
@@ -45,58 +54,456 @@ impl Parser {
     }
 
     pub fn parse(&self, syntax: String) -> ParserResult {
-        let characters = syntax.chars();
-        let subgrids_digits = self.parse_characters(characters)?;
-        let subgrids = subgrids_digits
-            .into_iter()
-            .map(Subgrid::new)
-            .collect::<Vec<Subgrid>>();
-        let grid = Grid::new(subgrids);
+        let (digits, declarations) = Self::split_declarations(&syntax);
+        let characters = digits.chars();
+        let digits = self.parse_characters(characters)?;
+        let constraints = self.parse_declarations(&declarations)?;
+        let grid = Grid::from_cells(digits, constraints);
         Ok(grid)
     }
 
-    fn parse_characters(&self, characters: Chars) -> Result<Vec<Vec<Digit>>, ParserError> {
-        let mut digit_counter = 0;
-        let mut subgrids_digits: Vec<Vec<_>> = vec![Vec::default(); GRID_LENGTH];
+    pub fn parse_line(&self, syntax: String) -> ParserResult {
+        let (syntax, declarations) = Self::split_declarations(&syntax);
+        let mut digits = String::with_capacity(GRID_CELLS);
+        for character in syntax
+            .chars()
+            .filter(|character| !character.is_whitespace())
+        {
+            match character {
+                '0'..='9' => digits.push(character),
+                '.' => digits.push('0'),
+                _ => return Err(format!("Invalid character: '{}'", character).into()),
+            }
+        }
+        let mut grid = self.parse(digits)?;
+        grid.constraints = self.parse_declarations(&declarations)?;
+        Ok(grid)
+    }
+
+    // Samurai grids are written as their 21x21 layout, with spaces in place of the empty boxes
+    // between the corner grids.
+    pub fn parse_samurai(&self, syntax: String) -> Result<Samurai, ParserError> {
+        let lines = syntax.trim_end().lines().collect::<Vec<_>>();
+        if lines.len() != SAMURAI_SIZE {
+            let message = format!("Expected {} lines, found {}", SAMURAI_SIZE, lines.len());
+            return Err(message.into());
+        }
+        let mut samurai = Samurai::default();
+        for (y, line) in lines.into_iter().enumerate() {
+            let characters = line.trim_end().chars().collect::<Vec<_>>();
+            if characters.len() > SAMURAI_SIZE {
+                let message = format!("Line {} is longer than {} characters", y + 1, SAMURAI_SIZE);
+                return Err(message.into());
+            }
+            for x in 0..SAMURAI_SIZE {
+                let character = characters.get(x).copied().unwrap_or(' ');
+                let (row, column) = (y + 1, x + 1);
+                if !Samurai::is_covered(x, y) {
+                    if character != ' ' {
+                        let message = format!("Cell r{}c{} is outside of the grids", row, column);
+                        return Err(message.into());
+                    }
+                    continue;
+                }
+                let digit = match character {
+                    '1'..='9' => Digit::from(character.to_digit(10).unwrap()),
+                    '0' | '.' => Digit::default(),
+                    ' ' => return Err(format!("Cell r{}c{} is missing", row, column).into()),
+                    _ => return Err(format!("Invalid character: '{}'", character).into()),
+                };
+                samurai.set_digit(x, y, digit);
+            }
+        }
+        Ok(samurai)
+    }
+
+    // Lines starting with a letter declare the constraints of a variant, the remaining lines hold
+    // the digits.
+    fn split_declarations(syntax: &str) -> (String, Vec<&str>) {
+        let mut digits = String::with_capacity(syntax.len());
+        let mut declarations = Vec::new();
+        for line in syntax.lines() {
+            let line = line.trim();
+            if line.starts_with(|character: char| character.is_ascii_alphabetic()) {
+                declarations.push(line);
+            } else {
+                digits.push_str(line);
+                digits.push('\n');
+            }
+        }
+        (digits, declarations)
+    }
+
+    fn parse_declarations(&self, declarations: &[&str]) -> Result<Constraints, ParserError> {
+        let mut constraints = Constraints::new();
+        let mut declarations = declarations.iter();
+        while let Some(declaration) = declarations.next() {
+            let mut words = declaration.split_whitespace();
+            let keyword = words.next().unwrap_or_default();
+            let arguments = words.collect::<Vec<_>>();
+            match keyword {
+                "diagonal" => {
+                    Self::expect_no_arguments(keyword, &arguments)?;
+                    constraints.diagonal = true;
+                }
+                "windows" => {
+                    Self::expect_no_arguments(keyword, &arguments)?;
+                    constraints.windows = true;
+                }
+                "non-consecutive" => {
+                    Self::expect_no_arguments(keyword, &arguments)?;
+                    constraints.non_consecutive = true;
+                }
+                "negative-dots" => {
+                    Self::expect_no_arguments(keyword, &arguments)?;
+                    constraints.negative_dots = true;
+                }
+                "dot" => {
+                    let dot = Self::parse_dot(&arguments)?;
+                    if constraints.get_dot(&dot.first, &dot.second).is_some() {
+                        return Err("Cells are joined by more than one dot".into());
+                    }
+                    constraints.dots.push(dot);
+                }
+                "cage" => {
+                    let cage = Self::parse_cage(&arguments)?;
+                    let overlapping = cage
+                        .cells
+                        .iter()
+                        .find(|cell| constraints.get_cage(cell.column(), cell.row()).is_some());
+                    if let Some(cell) = overlapping {
+                        let (row, column) = (cell.row() + 1, cell.column() + 1);
+                        let message = format!("Cell r{}c{} is in more than one cage", row, column);
+                        return Err(message.into());
+                    }
+                    constraints.cages.push(cage);
+                }
+                "thermometer" => {
+                    let cells = Self::parse_path(keyword, &arguments)?;
+                    if cells.len() > SUBGRID_LENGTH {
+                        let message = format!("Thermometer has more than {} cells", SUBGRID_LENGTH);
+                        return Err(message.into());
+                    }
+                    constraints.thermometers.push(Thermometer::new(cells));
+                }
+                "arrow" => {
+                    let mut cells = Self::parse_path(keyword, &arguments)?;
+                    let circle = cells.remove(0);
+                    constraints.arrows.push(Arrow::new(circle, cells));
+                }
+                "sandwich" => {
+                    let sandwich = Self::parse_sandwich(&arguments)?;
+                    if constraints
+                        .sandwiches
+                        .iter()
+                        .any(|other| other.line == sandwich.line)
+                    {
+                        let (name, index) = (sandwich.line.name(), sandwich.line.index() + 1);
+                        let message = format!("Sandwich in {} {} is declared twice", name, index);
+                        return Err(message.into());
+                    }
+                    constraints.sandwiches.push(sandwich);
+                }
+                "odd" | "even" => {
+                    let kind = ParityKind::from_name(keyword).unwrap();
+                    if arguments.is_empty() {
+                        let message =
+                            format!("Declaration '{}' expects at least one cell", keyword);
+                        return Err(message.into());
+                    }
+                    for argument in &arguments {
+                        let cell = Self::parse_cell(argument)?;
+                        if constraints
+                            .parities
+                            .iter()
+                            .any(|parity| parity.cell == cell)
+                        {
+                            let (row, column) = (cell.row() + 1, cell.column() + 1);
+                            let message =
+                                format!("Cell r{}c{} has more than one parity marker", row, column);
+                            return Err(message.into());
+                        }
+                        constraints.parities.push(Parity::new(kind, cell));
+                    }
+                }
+                "inequality" => {
+                    let inequality = Self::parse_inequality(&arguments)?;
+                    let (smaller, greater) = (&inequality.smaller, &inequality.greater);
+                    if constraints
+                        .inequalities
+                        .iter()
+                        .any(|other| other.connects(smaller, greater))
+                    {
+                        return Err("Cells are joined by more than one inequality".into());
+                    }
+                    constraints.inequalities.push(inequality);
+                }
+                "regions" => {
+                    Self::expect_no_arguments(keyword, &arguments)?;
+                    if constraints.regions.is_some() {
+                        return Err("Regions are declared more than once".into());
+                    }
+                    let rows = declarations.by_ref().take(GRID_JOINT_SIZE).copied();
+                    let rows = rows.collect::<Vec<_>>();
+                    constraints.regions = Some(Self::parse_regions(&rows)?);
+                }
+                _ => match Relation::from_name(keyword) {
+                    Some(relation) => {
+                        Self::expect_no_arguments(keyword, &arguments)?;
+                        if !constraints.relations.contains(&relation) {
+                            constraints.relations.push(relation);
+                        }
+                    }
+                    None => return Err(format!("Unknown declaration: '{}'", keyword).into()),
+                },
+            }
+        }
+        Ok(constraints)
+    }
+
+    fn parse_dot(arguments: &[&str]) -> Result<Dot, ParserError> {
+        let (kind, first, second) = match arguments {
+            [kind, first, second] => (kind, first, second),
+            _ => return Err("Declaration 'dot' expects a color and two cells".into()),
+        };
+        let kind =
+            DotKind::from_name(kind).ok_or_else(|| format!("Invalid dot color: '{}'", kind))?;
+        let (first, second) = (Self::parse_cell(first)?, Self::parse_cell(second)?);
+        if !get_adjacent_cells(first.column(), first.row()).contains(&second) {
+            return Err("Dot cells are not adjacent".into());
+        }
+        Ok(Dot::new(kind, first, second))
+    }
+
+    // The sign points to the smaller cell, like in 'inequality r1c1 < r1c2'.
+    fn parse_inequality(arguments: &[&str]) -> Result<Inequality, ParserError> {
+        let (first, sign, second) = match arguments {
+            [first, sign, second] => (first, sign, second),
+            _ => {
+                let message = "Declaration 'inequality' expects two cells with a sign between them";
+                return Err(message.into());
+            }
+        };
+        let (first, second) = (Self::parse_cell(first)?, Self::parse_cell(second)?);
+        if !get_adjacent_cells(first.column(), first.row()).contains(&second) {
+            return Err("Inequality cells are not adjacent".into());
+        }
+        match *sign {
+            "<" => Ok(Inequality::new(first, second)),
+            ">" => Ok(Inequality::new(second, first)),
+            _ => Err(format!("Invalid inequality sign: '{}'", sign).into()),
+        }
+    }
+
+    // Paths start at the bulb of a thermometer or the circle of an arrow and go through cells that
+    // touch each other.
+    fn parse_path(keyword: &str, arguments: &[&str]) -> Result<Vec<Cell>, ParserError> {
+        if arguments.len() < 2 {
+            return Err(format!("Declaration '{}' expects at least two cells", keyword).into());
+        }
+        let cells = arguments
+            .iter()
+            .map(|cell| Self::parse_cell(cell))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (index, cell) in cells.iter().enumerate() {
+            if cells[..index].contains(cell) {
+                let (row, column) = (cell.row() + 1, cell.column() + 1);
+                let message = format!("Cell r{}c{} is repeated in {}", row, column, keyword);
+                return Err(message.into());
+            }
+        }
+        if !is_path(&cells) {
+            return Err(format!("Cells of {} do not touch each other", keyword).into());
+        }
+        Ok(cells)
+    }
+
+    fn parse_sandwich(arguments: &[&str]) -> Result<Sandwich, ParserError> {
+        let (line, index, sum) = match arguments {
+            [line, index, sum] => (line, index, sum),
+            _ => return Err("Declaration 'sandwich' expects a row or a column and a sum".into()),
+        };
+        let to_line: fn(usize) -> Line = match *line {
+            "row" => Line::Row,
+            "column" => Line::Column,
+            _ => return Err(format!("Invalid sandwich line: '{}'", line).into()),
+        };
+        let index = index
+            .parse::<usize>()
+            .ok()
+            .filter(|index| (1..=GRID_JOINT_SIZE).contains(index))
+            .ok_or_else(|| format!("Invalid sandwich {}: '{}'", line, index))?;
+        let sum = sum
+            .parse::<u32>()
+            .ok()
+            .filter(|sum| *sum <= MAX_SANDWICH_SUM)
+            .ok_or_else(|| format!("Invalid sandwich sum: '{}'", sum))?;
+        Ok(Sandwich::new(to_line(index - 1), sum))
+    }
+
+    fn parse_cage(arguments: &[&str]) -> Result<Cage, ParserError> {
+        let (sum, cells) = match arguments.split_first() {
+            Some((sum, cells)) if !cells.is_empty() => (sum, cells),
+            _ => return Err("Declaration 'cage' expects a sum and cells".into()),
+        };
+        let sum = sum
+            .parse::<u32>()
+            .ok()
+            .filter(|sum| (1..=MAX_CAGE_SUM).contains(sum))
+            .ok_or_else(|| format!("Invalid cage sum: '{}'", sum))?;
+        let cells = cells
+            .iter()
+            .map(|cell| Self::parse_cell(cell))
+            .collect::<Result<Vec<_>, _>>()?;
+        if cells.len() > SUBGRID_LENGTH {
+            return Err(format!("Cage has more than {} cells", SUBGRID_LENGTH).into());
+        }
+        for (index, cell) in cells.iter().enumerate() {
+            if cells[..index].contains(cell) {
+                let (row, column) = (cell.row() + 1, cell.column() + 1);
+                return Err(format!("Cell r{}c{} is repeated in a cage", row, column).into());
+            }
+        }
+        if !Self::is_connected(&cells) {
+            return Err("Cage cells are not connected".into());
+        }
+        Ok(Cage::new(sum, cells))
+    }
+
+    // Regions are given as a map with a letter for every cell, cells of a region sharing the
+    // letter. Regions are numbered in the order in which their letters first appear.
+    fn parse_regions(rows: &[&str]) -> Result<Regions, ParserError> {
+        if rows.len() != GRID_JOINT_SIZE {
+            let message = format!("Expected {} rows of regions", GRID_JOINT_SIZE);
+            return Err(message.into());
+        }
+        let mut letters = Vec::with_capacity(SUBGRID_LENGTH);
+        let mut cells = Vec::with_capacity(GRID_CELLS);
+        for row in rows {
+            if row.chars().count() != GRID_JOINT_SIZE {
+                let message = format!("Expected {} regions in row '{}'", GRID_JOINT_SIZE, row);
+                return Err(message.into());
+            }
+            for letter in row.chars() {
+                if !letter.is_ascii_alphabetic() {
+                    return Err(format!("Invalid region: '{}'", letter).into());
+                }
+                let region = match letters.iter().position(|other| *other == letter) {
+                    Some(region) => region,
+                    None => {
+                        letters.push(letter);
+                        letters.len() - 1
+                    }
+                };
+                cells.push(region);
+            }
+        }
+        if letters.len() != SUBGRID_LENGTH {
+            let message = format!(
+                "Expected {} regions, found {}",
+                SUBGRID_LENGTH,
+                letters.len()
+            );
+            return Err(message.into());
+        }
+        let regions = Regions::new(cells);
+        for (region, letter) in letters.into_iter().enumerate() {
+            let cells = regions.get_cells(region);
+            if cells.len() != SUBGRID_LENGTH {
+                let message = format!(
+                    "Region '{}' has {} cells instead of {}",
+                    letter,
+                    cells.len(),
+                    SUBGRID_LENGTH
+                );
+                return Err(message.into());
+            }
+            if !Self::is_connected(&cells) {
+                return Err(format!("Region '{}' is not connected", letter).into());
+            }
+        }
+        Ok(regions)
+    }
+
+    // Cells are written as `r<row>c<column>`, both counted from 1.
+    fn parse_cell(cell: &str) -> Result<Cell, ParserError> {
+        let invalid = || format!("Invalid cell: '{}'", cell);
+        let (row, column) = cell
+            .strip_prefix('r')
+            .and_then(|cell| cell.split_once('c'))
+            .ok_or_else(invalid)?;
+        let parse_coordinate = |coordinate: &str| {
+            coordinate
+                .parse::<usize>()
+                .ok()
+                .filter(|coordinate| (1..=GRID_JOINT_SIZE).contains(coordinate))
+                .ok_or_else(invalid)
+        };
+        let (y, x) = (parse_coordinate(row)?, parse_coordinate(column)?);
+        Ok(Cell::new(x - 1, y - 1))
+    }
+
+    fn is_connected(cells: &[Cell]) -> bool {
+        let mut reached = vec![cells[0]];
+        let mut index = 0;
+        while index < reached.len() {
+            let current = reached[index];
+            for cell in cells {
+                let distance =
+                    current.column().abs_diff(cell.column()) + current.row().abs_diff(cell.row());
+                if distance == 1 && !reached.contains(cell) {
+                    reached.push(*cell);
+                }
+            }
+            index += 1;
+        }
+        reached.len() == cells.len()
+    }
+
+    fn expect_no_arguments(keyword: &str, arguments: &[&str]) -> Result<(), ParserError> {
+        if arguments.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("Declaration '{}' takes no arguments", keyword).into())
+        }
+    }
+
+    fn parse_characters(&self, characters: Chars) -> Result<Vec<Digit>, ParserError> {
+        let mut digits = Vec::with_capacity(GRID_CELLS);
         for character in characters {
-            self.parse_character(character, &mut digit_counter, &mut subgrids_digits)?;
+            self.parse_character(character, &mut digits)?;
         }
-        Ok(subgrids_digits)
+        if digits.len() != GRID_CELLS {
+            let message = format!("Expected {} digits, found {}", GRID_CELLS, digits.len());
+            return Err(message.into());
+        }
+        Ok(digits)
     }
 
-    fn parse_character(
-        &self,
-        character: char,
-        digit_counter: &mut usize,
-        subgrids_digits: &mut Vec<Vec<Digit>>,
-    ) -> Result<(), ParserError> {
+    fn parse_character(&self, character: char, digits: &mut Vec<Digit>) -> Result<(), ParserError> {
         match character {
-            '0'..='9' => self.parse_digit(character, digit_counter, subgrids_digits),
+            '0'..='9' => self.parse_digit(character, digits),
             ',' | ';' | '\n' | ' ' => Ok(()),
             _ => Err(format!("Invalid character: '{}'", character).into()),
         }
     }
 
-    fn parse_digit(
-        &self,
-        character: char,
-        digit_counter: &mut usize,
-        subgrids_digits: &mut Vec<Vec<Digit>>,
-    ) -> Result<(), ParserError> {
-        let counter = *digit_counter;
-        let row = counter / (GRID_JOINT_SIZE * GRID_SIZE);
-        let index = GRID_SIZE * row + (counter / SUBGRID_SIZE) % GRID_SIZE;
-        let digits = subgrids_digits.get_mut(index).ok_or("Invalid index")?;
+    fn parse_digit(&self, character: char, digits: &mut Vec<Digit>) -> Result<(), ParserError> {
+        if digits.len() >= GRID_CELLS {
+            return Err(format!("Expected {} digits, found more", GRID_CELLS).into());
+        }
         let digit = character.to_digit(10).ok_or("Invalid character")?;
-        let digit = Digit::from(digit);
-        digits.push(digit);
-        *digit_counter += 1;
+        digits.push(Digit::from(digit));
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::grid::cell_constraint::Line;
+    use crate::grid::generator::Generator;
+    use crate::grid::Subgrid;
+
     use super::*;
 
     type TestResult = Result<(), ParserError>;
@@ -120,4 +527,236 @@ mod tests {
         assert_eq!(expected, actual);
         Ok(())
     }
+
+    #[test]
+    fn test_grid_with_missing_digits_is_rejected() {
+        let parser = Parser::new();
+        let syntax = "1, 2, 3;  4, 5, 6;  7, 8, 9;".to_owned();
+        assert!(parser.parse(syntax).is_err());
+    }
+
+    #[test]
+    fn test_declarations_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let mut syntax = include_str!("test.grid").to_owned();
+        syntax.push_str("\ndiagonal\n");
+        let grid = parser.parse(syntax)?;
+        assert!(grid.constraints.diagonal);
+        let syntax = format!("{}\ndiagonal", ".".repeat(GRID_CELLS));
+        assert!(parser.parse_line(syntax)?.constraints.diagonal);
+        let syntax = format!("{}\nhexagonal", ".".repeat(GRID_CELLS));
+        assert!(parser.parse_line(syntax).is_err());
+        let syntax = format!("{}\ndiagonal 1", ".".repeat(GRID_CELLS));
+        assert!(parser.parse_line(syntax).is_err());
+        let syntax = format!("{}\nwindows", ".".repeat(GRID_CELLS));
+        assert!(parser.parse_line(syntax)?.constraints.windows);
+        let syntax = format!("{}\nanti-king\nanti-knight", ".".repeat(GRID_CELLS));
+        let relations = parser.parse_line(syntax)?.constraints.relations;
+        assert_eq!(vec![Relation::AntiKing, Relation::AntiKnight], relations);
+        Ok(())
+    }
+
+    #[test]
+    fn test_regions_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let regions = "regions\nAAAABBCCC\nAAABBBCCC\nAABBBBCCC\nGDEEEFFFF\nGDDDEFFFF\n\
+                       GGDHEEEEF\nGDDHHEIII\nGDDHHHIII\nGGGHHHIII";
+        let syntax = format!("{}\n{}", ".".repeat(GRID_CELLS), regions);
+        let grid = parser.parse_line(syntax)?;
+        let constraints = &grid.constraints;
+        assert!(!constraints.has_subgrid_units());
+        assert_eq!(0, constraints.get_region(3, 0));
+        assert_eq!(1, constraints.get_region(4, 0));
+        assert_eq!(3, constraints.get_region(0, 3));
+        assert_eq!(4, constraints.get_region(1, 3));
+        for (from, to) in [
+            ("AAAABBCCC", "AAAABBCC"),
+            ("AAAABBCCC", "AAAAB.CCC"),
+            ("AAAABBCCC", "AAAABBCCJ"),
+            ("GGGHHHIII", "GGGHHIHII"),
+            ("GGGHHHIII", "GGGHHHIIG"),
+            ("GGGHHHIII", ""),
+        ] {
+            let syntax = format!(
+                "{}\n{}",
+                ".".repeat(GRID_CELLS),
+                regions.replacen(from, to, 1)
+            );
+            assert!(parser.parse_line(syntax).is_err(), "{} -> {}", from, to);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cages_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let empty = ".".repeat(GRID_CELLS);
+        let syntax = format!("{}\ncage 10 r1c1 r1c2\ncage 3 r9c9", empty);
+        let cages = parser.parse_line(syntax)?.constraints.cages;
+        assert_eq!(2, cages.len());
+        assert_eq!(10, cages[0].sum);
+        assert_eq!(vec![Cell::new(0, 0), Cell::new(1, 0)], cages[0].cells);
+        assert_eq!(vec![Cell::new(8, 8)], cages[1].cells);
+        for declaration in [
+            "cage 10",
+            "cage 0 r1c1",
+            "cage 46 r1c1",
+            "cage 5 r0c1",
+            "cage 5 r1c10",
+            "cage 5 c1r1",
+            "cage 5 r1c1 r1c1",
+            "cage 5 r1c1 r2c2",
+            "cage 5 r1c1\ncage 5 r1c2 r1c1",
+        ] {
+            let syntax = format!("{}\n{}", empty, declaration);
+            assert!(parser.parse_line(syntax).is_err(), "{}", declaration);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_dots_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let empty = ".".repeat(GRID_CELLS);
+        let syntax = format!(
+            "{}\nnon-consecutive\ndot white r1c1 r1c2\ndot black r2c1 r1c1\nnegative-dots",
+            empty
+        );
+        let constraints = parser.parse_line(syntax)?.constraints;
+        assert!(constraints.non_consecutive && constraints.negative_dots);
+        assert_eq!(2, constraints.dots.len());
+        assert_eq!(DotKind::White, constraints.dots[0].kind);
+        assert_eq!(DotKind::Black, constraints.dots[1].kind);
+        assert_eq!(Cell::new(0, 1), constraints.dots[1].first);
+        for declaration in [
+            "dot white r1c1",
+            "dot grey r1c1 r1c2",
+            "dot white r1c1 r2c2",
+            "dot white r1c1 r1c1",
+            "dot white r1c1 r1c2\ndot black r1c2 r1c1",
+            "non-consecutive 1",
+        ] {
+            let syntax = format!("{}\n{}", empty, declaration);
+            assert!(parser.parse_line(syntax).is_err(), "{}", declaration);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_cell_constraints_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let empty = ".".repeat(GRID_CELLS);
+        let syntax = format!(
+            "{}\nthermometer r1c1 r2c2 r2c3\narrow r5c5 r5c6\nsandwich row 2 10\nsandwich column 9 0",
+            empty
+        );
+        let constraints = parser.parse_line(syntax)?.constraints;
+        assert_eq!(
+            vec![Thermometer::new(vec![
+                Cell::new(0, 0),
+                Cell::new(1, 1),
+                Cell::new(2, 1)
+            ])],
+            constraints.thermometers
+        );
+        let arrow = Arrow::new(Cell::new(4, 4), vec![Cell::new(5, 4)]);
+        assert_eq!(vec![arrow], constraints.arrows);
+        let sandwiches = vec![
+            Sandwich::new(Line::Row(1), 10),
+            Sandwich::new(Line::Column(8), 0),
+        ];
+        assert_eq!(sandwiches, constraints.sandwiches);
+        for declaration in [
+            "thermometer r1c1",
+            "thermometer r1c1 r1c3",
+            "thermometer r1c1 r1c2 r1c1",
+            "thermometer r1c1 r1c2 r1c3 r1c4 r1c5 r1c6 r1c7 r1c8 r1c9 r2c9",
+            "arrow r1c1",
+            "sandwich row 1",
+            "sandwich diagonal 1 5",
+            "sandwich row 10 5",
+            "sandwich column 1 36",
+            "sandwich row 1 5\nsandwich row 1 6",
+        ] {
+            let syntax = format!("{}\n{}", empty, declaration);
+            assert!(parser.parse_line(syntax).is_err(), "{}", declaration);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parities_and_inequalities_are_parsed() -> TestResult {
+        let parser = Parser::new();
+        let empty = ".".repeat(GRID_CELLS);
+        let syntax = format!(
+            "{}\nodd r1c1 r3c5\neven r2c2\ninequality r1c1 < r1c2\ninequality r2c1 > r1c1",
+            empty
+        );
+        let constraints = parser.parse_line(syntax)?.constraints;
+        let parities = vec![
+            Parity::new(ParityKind::Odd, Cell::new(0, 0)),
+            Parity::new(ParityKind::Odd, Cell::new(4, 2)),
+            Parity::new(ParityKind::Even, Cell::new(1, 1)),
+        ];
+        assert_eq!(parities, constraints.parities);
+        let inequalities = vec![
+            Inequality::new(Cell::new(0, 0), Cell::new(1, 0)),
+            Inequality::new(Cell::new(0, 0), Cell::new(0, 1)),
+        ];
+        assert_eq!(inequalities, constraints.inequalities);
+        for declaration in [
+            "odd",
+            "even r1c1 r10c1",
+            "odd r1c1\neven r1c1",
+            "inequality r1c1 r1c2",
+            "inequality r1c1 = r1c2",
+            "inequality r1c1 < r2c2",
+            "inequality r1c1 < r1c2\ninequality r1c2 > r1c1",
+        ] {
+            let syntax = format!("{}\n{}", empty, declaration);
+            assert!(parser.parse_line(syntax).is_err(), "{}", declaration);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_line_is_parsed_correctly() -> TestResult {
+        let parser = Parser::new();
+        let expected = parser.parse(include_str!("test.grid").to_owned())?;
+        let syntax = "1239871..456654...789321..9987123900654456000321789001\n\
+                      ...........................\n"
+            .to_owned();
+        let actual = parser.parse_line(syntax)?;
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_samurai_is_parsed_correctly() -> TestResult {
+        let parser = Parser::new();
+        let syntax = include_str!("../../tests/grids/samurai.grid");
+        let samurai = parser.parse_samurai(syntax.to_owned())?;
+        assert_eq!(Some(&Digit::Known(5)), samurai.get_digit(2, 0));
+        assert_eq!(Some(&Digit::Known(2)), samurai.get_digit(6, 9));
+        assert_eq!(&Digit::Known(2), samurai.grids[2].get_digit(0, 3));
+        assert_eq!(None, samurai.get_digit(9, 0));
+        assert!(samurai.is_consistent());
+        let output = Generator::new().generate_samurai(&samurai)?;
+        assert_eq!(syntax, output);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_samurai_is_rejected() {
+        let parser = Parser::new();
+        let syntax = include_str!("../../tests/grids/samurai.grid");
+        let mut lines = syntax.lines().map(str::to_owned).collect::<Vec<_>>();
+        assert!(parser.parse_samurai(lines[..20].join("\n")).is_err());
+        lines[0].push('1');
+        assert!(parser.parse_samurai(lines.join("\n")).is_err());
+        lines[0] = "..5....1.  1.....9...".to_owned();
+        assert!(parser.parse_samurai(lines.join("\n")).is_err());
+        lines[0] = "..5....1".to_owned();
+        assert!(parser.parse_samurai(lines.join("\n")).is_err());
+    }
 }
@@ -0,0 +1,513 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::char::from_digit;
+use std::cmp::Ordering;
+
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::{CellConstraint, Line};
+use crate::grid::constraints::DotKind;
+use crate::grid::digit::Digit;
+use crate::grid::samurai::{Samurai, SAMURAI_BOXES, SAMURAI_SIZE};
+use crate::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_SIZE};
+
+pub type RendererResult = Result<String, RendererError>;
+pub type RendererError = Cow<'static, str>;
+
+const CELL_WIDTH: usize = 7;
+const CELL_HEIGHT: usize = 3;
+const WIDTH: usize = GRID_JOINT_SIZE * (CELL_WIDTH + 1) + 1;
+const HEIGHT: usize = GRID_JOINT_SIZE * (CELL_HEIGHT + 1) + 1;
+// Sandwich sums are written after the end of the rows and below the columns.
+const CANVAS_WIDTH: usize = WIDTH + 4;
+const CANVAS_HEIGHT: usize = HEIGHT + 2;
+
+// Draws the grid on a character canvas with room inside each cell for the outlines of variant
+// constraints, like Killer Sudoku cages, which are drawn dashed along the inner edge of the cells.
+pub struct Renderer;
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Renderer {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn render(&self, grid: &Grid) -> RendererResult {
+        let mut canvas = Canvas::new(CANVAS_WIDTH, CANVAS_HEIGHT);
+        self.draw_lines(grid, &mut canvas);
+        self.draw_cages(grid, &mut canvas);
+        self.draw_dots(grid, &mut canvas);
+        self.draw_inequalities(grid, &mut canvas);
+        self.draw_paths(grid, &mut canvas);
+        self.draw_parities(grid, &mut canvas);
+        self.draw_sandwiches(grid, &mut canvas);
+        self.draw_digits(grid, &mut canvas);
+        Ok(canvas.into_string())
+    }
+
+    // Samurai grids are drawn compactly, with only the boxes outlined, since the cells have no
+    // room for variant constraints.
+    pub fn render_samurai(&self, samurai: &Samurai) -> RendererResult {
+        let box_width = SUBGRID_SIZE * 2 + 2;
+        let box_height = SUBGRID_SIZE + 1;
+        let width = SAMURAI_BOXES * box_width + 1;
+        let height = SAMURAI_BOXES * box_height + 1;
+        let mut canvas = Canvas::new(width, height);
+        for box_y in 0..SAMURAI_BOXES {
+            for box_x in 0..SAMURAI_BOXES {
+                if !Samurai::is_box_covered(box_x, box_y) {
+                    continue;
+                }
+                let (left, top) = (box_x * box_width, box_y * box_height);
+                for x in left..=left + box_width {
+                    let character = if x == left || x == left + box_width {
+                        '+'
+                    } else {
+                        '-'
+                    };
+                    canvas.set(x, top, character);
+                    canvas.set(x, top + box_height, character);
+                }
+                for y in top + 1..top + box_height {
+                    canvas.set(left, y, '|');
+                    canvas.set(left + box_width, y, '|');
+                }
+            }
+        }
+        for y in 0..SAMURAI_SIZE {
+            for x in 0..SAMURAI_SIZE {
+                if let Some(Digit::Known(value)) = samurai.get_digit(x, y) {
+                    let canvas_x = x / SUBGRID_SIZE * box_width + 2 + x % SUBGRID_SIZE * 2;
+                    let canvas_y = y / SUBGRID_SIZE * box_height + 1 + y % SUBGRID_SIZE;
+                    canvas.set(canvas_x, canvas_y, from_digit(*value, 10).unwrap());
+                }
+            }
+        }
+        Ok(canvas.into_string())
+    }
+
+    fn draw_lines(&self, grid: &Grid, canvas: &mut Canvas) {
+        let constraints = &grid.constraints;
+        let is_same_region = |(first_x, first_y), (second_x, second_y)| {
+            constraints.get_region(first_x, first_y) == constraints.get_region(second_x, second_y)
+        };
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let character = match (x % (CELL_WIDTH + 1), y % (CELL_HEIGHT + 1)) {
+                    (0, 0) => '+',
+                    (0, _) => '|',
+                    (_, 0) => '-',
+                    _ => continue,
+                };
+                canvas.set(x, y, character);
+            }
+        }
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..=GRID_JOINT_SIZE {
+                if x == 0 || x == GRID_JOINT_SIZE || !is_same_region((x - 1, y), (x, y)) {
+                    let left = x * (CELL_WIDTH + 1);
+                    let top = y * (CELL_HEIGHT + 1);
+                    (top..=top + CELL_HEIGHT + 1).for_each(|y| canvas.set(left, y, '#'));
+                }
+            }
+        }
+        for y in 0..=GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if y == 0 || y == GRID_JOINT_SIZE || !is_same_region((x, y - 1), (x, y)) {
+                    let left = x * (CELL_WIDTH + 1);
+                    let top = y * (CELL_HEIGHT + 1);
+                    (left..=left + CELL_WIDTH + 1).for_each(|x| canvas.set(x, top, '#'));
+                }
+            }
+        }
+    }
+
+    fn draw_cages(&self, grid: &Grid, canvas: &mut Canvas) {
+        for cage in &grid.constraints.cages {
+            let is_outside = |x: Option<usize>, y: Option<usize>| match (x, y) {
+                (Some(x), Some(y)) if x < GRID_JOINT_SIZE && y < GRID_JOINT_SIZE => {
+                    !cage.contains(x, y)
+                }
+                _ => true,
+            };
+            for cell in &cage.cells {
+                let (x, y) = (cell.column(), cell.row());
+                let left = x * (CELL_WIDTH + 1) + 1;
+                let top = y * (CELL_HEIGHT + 1) + 1;
+                let (right, bottom) = (left + CELL_WIDTH - 1, top + CELL_HEIGHT - 1);
+                if is_outside(x.checked_sub(1), Some(y)) {
+                    (top..=bottom).for_each(|y| canvas.set(left, y, ':'));
+                }
+                if is_outside(Some(x + 1), Some(y)) {
+                    (top..=bottom).for_each(|y| canvas.set(right, y, ':'));
+                }
+                if is_outside(Some(x), y.checked_sub(1)) {
+                    (left..=right)
+                        .step_by(2)
+                        .for_each(|x| canvas.set(x, top, '-'));
+                }
+                if is_outside(Some(x), Some(y + 1)) {
+                    (left..=right)
+                        .step_by(2)
+                        .for_each(|x| canvas.set(x, bottom, '-'));
+                }
+            }
+            // The sum is written in the corner of the first cell, in reading order.
+            if let Some(first) = cage
+                .cells
+                .iter()
+                .min_by_key(|cell| (cell.row(), cell.column()))
+            {
+                let left = first.column() * (CELL_WIDTH + 1) + 1;
+                let top = first.row() * (CELL_HEIGHT + 1) + 1;
+                canvas.write(left, top, &cage.sum.to_string());
+            }
+        }
+    }
+
+    // Dots are drawn on the middle of the border between the two cells they join.
+    fn draw_dots(&self, grid: &Grid, canvas: &mut Canvas) {
+        for dot in &grid.constraints.dots {
+            let first = dot.first.min(dot.second);
+            let (x, y) = (first.column(), first.row());
+            let (column, row) = if dot.connects(&first, &Cell::new(x + 1, y)) {
+                (
+                    (x + 1) * (CELL_WIDTH + 1),
+                    y * (CELL_HEIGHT + 1) + 1 + CELL_HEIGHT / 2,
+                )
+            } else {
+                (
+                    x * (CELL_WIDTH + 1) + 1 + CELL_WIDTH / 2,
+                    (y + 1) * (CELL_HEIGHT + 1),
+                )
+            };
+            let character = match dot.kind {
+                DotKind::White => 'o',
+                DotKind::Black => '*',
+            };
+            canvas.set(column, row, character);
+        }
+    }
+
+    // Inequality signs are drawn on the border like dots, with the tip towards the smaller cell.
+    fn draw_inequalities(&self, grid: &Grid, canvas: &mut Canvas) {
+        for inequality in &grid.constraints.inequalities {
+            let (smaller, greater) = (&inequality.smaller, &inequality.greater);
+            let (x, y) = (
+                smaller.column().min(greater.column()),
+                smaller.row().min(greater.row()),
+            );
+            let (column, row, character) = if smaller.row() == greater.row() {
+                let character = if smaller.column() < greater.column() {
+                    '<'
+                } else {
+                    '>'
+                };
+                (
+                    (x + 1) * (CELL_WIDTH + 1),
+                    y * (CELL_HEIGHT + 1) + 1 + CELL_HEIGHT / 2,
+                    character,
+                )
+            } else {
+                let character = if smaller.row() < greater.row() {
+                    '^'
+                } else {
+                    'v'
+                };
+                (
+                    x * (CELL_WIDTH + 1) + 1 + CELL_WIDTH / 2,
+                    (y + 1) * (CELL_HEIGHT + 1),
+                    character,
+                )
+            };
+            canvas.set(column, row, character);
+        }
+    }
+
+    // Parity markers are written in the bottom right corner of the cell, away from cage sums.
+    fn draw_parities(&self, grid: &Grid, canvas: &mut Canvas) {
+        for parity in &grid.constraints.parities {
+            let name = parity.kind.name();
+            let right = parity.cell.column() * (CELL_WIDTH + 1) + CELL_WIDTH;
+            let bottom = parity.cell.row() * (CELL_HEIGHT + 1) + CELL_HEIGHT;
+            canvas.write(right + 1 - name.len(), bottom, name);
+        }
+    }
+
+    // Thermometers have their bulb and arrows their circle drawn around the digit of the first cell.
+    // Arrows also have a head after the digit of the last cell.
+    fn draw_paths(&self, grid: &Grid, canvas: &mut Canvas) {
+        let thermometers = grid
+            .constraints
+            .thermometers
+            .iter()
+            .map(|thermometer| (thermometer.get_cells(), ('(', ')')));
+        let arrows = grid
+            .constraints
+            .arrows
+            .iter()
+            .map(|arrow| (arrow.get_cells(), ('[', ']')));
+        for (cells, (opening, closing)) in thermometers.chain(arrows.clone()) {
+            let (center_x, center_y) = get_center(&cells[0]);
+            canvas.set(center_x - 1, center_y, opening);
+            canvas.set(center_x + 1, center_y, closing);
+            for pair in cells.windows(2) {
+                self.draw_step(&pair[0], &pair[1], canvas);
+            }
+        }
+        for (cells, _) in arrows {
+            let (last, previous) = (&cells[cells.len() - 1], &cells[cells.len() - 2]);
+            let (center_x, center_y) = get_center(last);
+            let (x, y, head) = match (
+                last.column().cmp(&previous.column()),
+                last.row().cmp(&previous.row()),
+            ) {
+                (_, Ordering::Greater) => (center_x, center_y + 1, 'v'),
+                (_, Ordering::Less) => (center_x, center_y - 1, '^'),
+                (Ordering::Greater, _) => (center_x + 2, center_y, '>'),
+                _ => (center_x - 2, center_y, '<'),
+            };
+            canvas.set(x, y, head);
+        }
+    }
+
+    // A step is drawn inside both cells, leaving the border between them untouched.
+    fn draw_step(&self, from: &Cell, to: &Cell, canvas: &mut Canvas) {
+        let (from_x, from_y) = get_center(from);
+        let (to_x, to_y) = get_center(to);
+        let (step_x, step_y) = (
+            to.column() as isize - from.column() as isize,
+            to.row() as isize - from.row() as isize,
+        );
+        let character = match (step_x, step_y) {
+            (_, 0) => '-',
+            (0, _) => '|',
+            (1, 1) | (-1, -1) => '\\',
+            _ => '/',
+        };
+        let offsets: &[isize] = if step_y == 0 { &[2, 3] } else { &[2] };
+        for offset in offsets {
+            let (x, y) = (step_x * offset, step_y.signum());
+            canvas.set(
+                (from_x as isize + x) as usize,
+                (from_y as isize + y) as usize,
+                character,
+            );
+            canvas.set(
+                (to_x as isize - x) as usize,
+                (to_y as isize - y) as usize,
+                character,
+            );
+        }
+    }
+
+    fn draw_sandwiches(&self, grid: &Grid, canvas: &mut Canvas) {
+        for sandwich in &grid.constraints.sandwiches {
+            let sum = sandwich.sum.to_string();
+            match sandwich.line {
+                Line::Row(y) => canvas.write(WIDTH + 1, get_center(&Cell::new(0, y)).1, &sum),
+                Line::Column(x) => canvas.write(get_center(&Cell::new(x, 0)).0, HEIGHT + 1, &sum),
+            }
+        }
+    }
+
+    fn draw_digits(&self, grid: &Grid, canvas: &mut Canvas) {
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Known(value) = grid.get_digit(x, y) {
+                    let (center_x, center_y) = get_center(&Cell::new(x, y));
+                    canvas.set(center_x, center_y, from_digit(*value, 10).unwrap());
+                }
+            }
+        }
+    }
+}
+
+fn get_center(cell: &Cell) -> (usize, usize) {
+    (
+        cell.column() * (CELL_WIDTH + 1) + 1 + CELL_WIDTH / 2,
+        cell.row() * (CELL_HEIGHT + 1) + 1 + CELL_HEIGHT / 2,
+    )
+}
+
+struct Canvas {
+    characters: Vec<Vec<char>>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize) -> Self {
+        Self {
+            characters: vec![vec![' '; width]; height],
+        }
+    }
+
+    fn set(&mut self, x: usize, y: usize, character: char) {
+        self.characters[y][x] = character;
+    }
+
+    fn write(&mut self, x: usize, y: usize, text: &str) {
+        for (offset, character) in text.chars().enumerate() {
+            self.set(x + offset, y, character);
+        }
+    }
+
+    fn into_string(self) -> String {
+        let mut result = String::new();
+        for line in &self.characters {
+            let line = line.iter().collect::<String>();
+            result.push_str(line.trim_end());
+            result.push('\n');
+        }
+        let length = result.trim_end().len();
+        result.truncate(length);
+        result.push('\n');
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::constraints::Regions;
+    use crate::grid::parser::Parser;
+
+    use super::*;
+
+    type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+    #[test]
+    fn test_digits_and_region_lines_are_rendered() -> TestResult {
+        let grid = Parser::new().parse(include_str!("test.grid").to_owned())?;
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(HEIGHT, lines.len());
+        assert_eq!("#".repeat(WIDTH), lines[0]);
+        assert_eq!("#       |       |       #", &lines[1][..25]);
+        assert_eq!("#   1   |   2   |   3   #", &lines[2][..25]);
+        assert_eq!("#-------+-------+-------#", &lines[4][..25]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_regions_are_bordered() -> TestResult {
+        let mut grid = Grid::default();
+        let regions = (0..GRID_JOINT_SIZE * GRID_JOINT_SIZE)
+            .map(|cell| cell / GRID_JOINT_SIZE)
+            .collect();
+        grid.constraints.regions = Some(Regions::new(regions));
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!("#       |       |       |", &lines[1][..25]);
+        assert_eq!("#".repeat(WIDTH), lines[CELL_HEIGHT + 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cages_are_outlined_with_sums() -> TestResult {
+        let syntax = format!("{}\ncage 17 r1c1 r1c2 r2c1", ".".repeat(81));
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!("#17- - -|- - - -|", &lines[1][..17]);
+        assert_eq!("#:      |      :|", &lines[2][..17]);
+        assert_eq!("#:      |- - - -|", &lines[3][..17]);
+        assert_eq!("#:     :|", &lines[5][..9]);
+        assert_eq!("#- - - -|", &lines[7][..9]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths_and_sandwiches_are_drawn() -> TestResult {
+        let syntax = format!(
+            "{}\nthermometer r1c1 r1c2 r2c3\narrow r3c1 r4c1\nsandwich row 1 12\nsandwich column 2 5",
+            "1".to_owned() + &".".repeat(80)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(HEIGHT + 2, lines.len());
+        assert_eq!("#  (1)--|--     |", &lines[2][..17]);
+        assert_eq!(" 12", &lines[2][WIDTH..]);
+        assert_eq!("#       |     \\ |", &lines[3][..17]);
+        assert_eq!("#       |       | \\", &lines[5][..19]);
+        assert_eq!("#  [ ]  |", &lines[10][..9]);
+        assert_eq!("#   |   |", &lines[11][..9]);
+        assert_eq!("#   |   |", &lines[13][..9]);
+        assert_eq!("#   v   |", &lines[15][..9]);
+        assert_eq!("            5", lines[HEIGHT + 1]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dots_are_drawn_between_cells() -> TestResult {
+        let syntax = format!(
+            "{}\ndot white r1c1 r1c2\ndot black r1c1 r2c1",
+            ".".repeat(81)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!("#       o       |", &lines[2][..17]);
+        assert_eq!("#---*---+-------+", &lines[4][..17]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parities_and_inequalities_are_drawn() -> TestResult {
+        let syntax = format!(
+            "{}\nodd r1c1\neven r2c2\ninequality r1c1 < r1c2\ninequality r2c1 > r1c1",
+            ".".repeat(81)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = Renderer::new().render(&grid)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!("#       <       |", &lines[2][..17]);
+        assert_eq!("#    odd|       |", &lines[3][..17]);
+        assert_eq!("#---^---+-------+", &lines[4][..17]);
+        assert_eq!("#       |   even|", &lines[7][..17]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_samurai_boxes_are_outlined() -> TestResult {
+        let syntax = include_str!("../../tests/grids/samurai.grid");
+        let samurai = Parser::new().parse_samurai(syntax.to_owned())?;
+        let output = Renderer::new().render_samurai(&samurai)?;
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(29, lines.len());
+        assert_eq!("+-------+-------+-------+       +-------+", &lines[0][..41]);
+        assert_eq!("|     5 |       |   1   |       |       |", &lines[1][..41]);
+        assert_eq!(
+            "+-------+-------+-------+-------+-------+",
+            &lines[12][..41]
+        );
+        assert_eq!("                | 2     |       |   9 7 |", lines[13]);
+        Ok(())
+    }
+}
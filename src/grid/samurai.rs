@@ -0,0 +1,168 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::digit::Digit;
+use crate::grid::validator::ValidationReport;
+use crate::grid::{Grid, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_SIZE};
+
+pub const SAMURAI_GRIDS: usize = 5;
+pub const SAMURAI_SIZE: usize = 2 * GRID_JOINT_SIZE + SUBGRID_SIZE;
+// The layout consists of 7x7 boxes, of which the ones between the corner grids are empty.
+pub const SAMURAI_BOXES: usize = 2 * GRID_SIZE + 1;
+
+// The top left cells of the grids in reading order, so the center grid comes third. Each corner
+// grid shares its inner box with a corner box of the center grid.
+pub const GRID_OFFSETS: [(usize, usize); SAMURAI_GRIDS] = [
+    (0, 0),
+    (GRID_JOINT_SIZE + SUBGRID_SIZE, 0),
+    (
+        GRID_JOINT_SIZE - SUBGRID_SIZE,
+        GRID_JOINT_SIZE - SUBGRID_SIZE,
+    ),
+    (0, GRID_JOINT_SIZE + SUBGRID_SIZE),
+    (
+        GRID_JOINT_SIZE + SUBGRID_SIZE,
+        GRID_JOINT_SIZE + SUBGRID_SIZE,
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Samurai {
+    pub grids: Vec<Grid>,
+}
+
+impl Default for Samurai {
+    fn default() -> Self {
+        Self::new(vec![Grid::default(); SAMURAI_GRIDS])
+    }
+}
+
+impl Samurai {
+    pub fn new(grids: Vec<Grid>) -> Self {
+        Self { grids }
+    }
+
+    // Returns the indices of the grids covering the cell together with the coordinates of the
+    // cell within each of them. Cells of the shared boxes are covered by two grids.
+    pub fn locate(x: usize, y: usize) -> Vec<(usize, usize, usize)> {
+        GRID_OFFSETS
+            .iter()
+            .enumerate()
+            .filter(|(_, (left, top))| {
+                (*left..left + GRID_JOINT_SIZE).contains(&x)
+                    && (*top..top + GRID_JOINT_SIZE).contains(&y)
+            })
+            .map(|(index, (left, top))| (index, x - left, y - top))
+            .collect()
+    }
+
+    pub fn is_covered(x: usize, y: usize) -> bool {
+        !Self::locate(x, y).is_empty()
+    }
+
+    // Whether the box at the given box coordinates of the 7x7 layout belongs to any grid.
+    pub fn is_box_covered(box_x: usize, box_y: usize) -> bool {
+        Self::is_covered(box_x * SUBGRID_SIZE, box_y * SUBGRID_SIZE)
+    }
+
+    pub fn get_digit(&self, x: usize, y: usize) -> Option<&Digit> {
+        Self::locate(x, y)
+            .first()
+            .map(|(index, grid_x, grid_y)| self.grids[*index].get_digit(*grid_x, *grid_y))
+    }
+
+    // Sets the digit in every grid covering the cell, which keeps the shared boxes in sync.
+    pub fn set_digit(&mut self, x: usize, y: usize, digit: Digit) {
+        for (index, grid_x, grid_y) in Self::locate(x, y) {
+            self.grids[index].set_digit(grid_x, grid_y, digit.clone());
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.grids.iter().all(Grid::is_solved)
+    }
+
+    pub fn is_solved_correctly(&self) -> bool {
+        self.grids.iter().all(Grid::is_solved_correctly) && self.is_consistent()
+    }
+
+    // Whether the shared cells hold the same digits in both grids covering them.
+    pub fn is_consistent(&self) -> bool {
+        (0..SAMURAI_SIZE).all(|y| {
+            (0..SAMURAI_SIZE).all(|x| {
+                let mut digits = Self::locate(x, y)
+                    .into_iter()
+                    .map(|(index, grid_x, grid_y)| self.grids[index].get_digit(grid_x, grid_y));
+                let first = digits.next();
+                digits.all(|digit| match (first, digit) {
+                    (Some(Digit::Known(first)), Digit::Known(value)) => first == value,
+                    (Some(Digit::Unknown(_)), Digit::Unknown(_)) => true,
+                    _ => false,
+                })
+            })
+        })
+    }
+
+    pub fn validate(&self) -> Vec<ValidationReport> {
+        self.grids.iter().map(Grid::validate).collect()
+    }
+
+    pub fn get_grid_name(index: usize) -> &'static str {
+        match index {
+            0 => "top left",
+            1 => "top right",
+            2 => "center",
+            3 => "bottom left",
+            _ => "bottom right",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_boxes_are_covered_by_two_grids() {
+        assert_eq!(vec![(0, 0, 0)], Samurai::locate(0, 0));
+        assert_eq!(vec![(0, 8, 8), (2, 2, 2)], Samurai::locate(8, 8));
+        assert_eq!(vec![(1, 0, 6), (2, 6, 0)], Samurai::locate(12, 6));
+        assert_eq!(vec![(2, 4, 4)], Samurai::locate(10, 10));
+        assert!(Samurai::locate(10, 0).is_empty());
+        assert!(!Samurai::is_box_covered(3, 0));
+        assert!(Samurai::is_box_covered(3, 2));
+    }
+
+    #[test]
+    fn test_digits_are_set_in_every_covering_grid() {
+        let mut samurai = Samurai::default();
+        samurai.set_digit(13, 14, Digit::Known(7));
+        assert_eq!(Some(&Digit::Known(7)), samurai.get_digit(13, 14));
+        assert_eq!(&Digit::Known(7), samurai.grids[2].get_digit(7, 8));
+        assert_eq!(&Digit::Known(7), samurai.grids[4].get_digit(1, 2));
+        assert!(samurai.is_consistent());
+        samurai.grids[4].set_digit(1, 2, Digit::Known(3));
+        assert!(!samurai.is_consistent());
+    }
+}
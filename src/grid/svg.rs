@@ -0,0 +1,477 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt::Write;
+
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::{Line, ParityKind};
+use crate::grid::constraints::{Cage, DotKind, UnitKind};
+use crate::grid::digit::Digit;
+use crate::grid::renderer::RendererResult;
+use crate::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_SIZE};
+
+const CELL_SIZE: usize = 40;
+const MARGIN: usize = 4;
+const SIZE: usize = GRID_JOINT_SIZE * CELL_SIZE + 2 * MARGIN;
+const CAGE_INSET: usize = 4;
+const DOT_RADIUS: usize = 5;
+const BULB_RADIUS: usize = 14;
+const CIRCLE_RADIUS: usize = 16;
+const ARROW_HEAD_LENGTH: f64 = 8.0;
+const PARITY_RADIUS: usize = 15;
+const CHEVRON_LENGTH: f64 = 6.0;
+// Sandwich sums are written after the end of the rows and below the columns.
+const CLUE_SIZE: usize = 24;
+
+// Renders the grid as a standalone SVG image, drawing the same variant constraints as the
+// character renderer: thick region borders, dashed cage outlines with sums and dots.
+pub struct SvgRenderer;
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SvgRenderer {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn render(&self, grid: &Grid) -> RendererResult {
+        let mut output = String::new();
+        let size = if grid.constraints.sandwiches.is_empty() {
+            SIZE
+        } else {
+            SIZE + CLUE_SIZE
+        };
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{0}" viewBox="0 0 {0} {0}">"#,
+            size
+        )
+        .unwrap();
+        writeln!(
+            output,
+            r#"<rect width="{0}" height="{0}" fill="white"/>"#,
+            size
+        )
+        .unwrap();
+        self.draw_windows(grid, &mut output);
+        self.draw_parities(grid, &mut output);
+        self.draw_thermometers(grid, &mut output);
+        self.draw_arrows(grid, &mut output);
+        self.draw_lines(grid, &mut output);
+        self.draw_cages(grid, &mut output);
+        self.draw_dots(grid, &mut output);
+        self.draw_inequalities(grid, &mut output);
+        self.draw_sandwiches(grid, &mut output);
+        self.draw_digits(grid, &mut output);
+        output.push_str("</svg>\n");
+        Ok(output)
+    }
+
+    fn draw_lines(&self, grid: &Grid, output: &mut String) {
+        let constraints = &grid.constraints;
+        let is_same_region = |(first_x, first_y), (second_x, second_y)| {
+            constraints.get_region(first_x, first_y) == constraints.get_region(second_x, second_y)
+        };
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..=GRID_JOINT_SIZE {
+                let thick = x == 0 || x == GRID_JOINT_SIZE || !is_same_region((x - 1, y), (x, y));
+                self.draw_line(output, (x, y), (x, y + 1), thick);
+            }
+        }
+        for y in 0..=GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                let thick = y == 0 || y == GRID_JOINT_SIZE || !is_same_region((x, y - 1), (x, y));
+                self.draw_line(output, (x, y), (x + 1, y), thick);
+            }
+        }
+    }
+
+    fn draw_line(
+        &self,
+        output: &mut String,
+        from: (usize, usize),
+        to: (usize, usize),
+        thick: bool,
+    ) {
+        let width = if thick { 3 } else { 1 };
+        writeln!(
+            output,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="{}" stroke-linecap="square"/>"#,
+            to_pixels(from.0),
+            to_pixels(from.1),
+            to_pixels(to.0),
+            to_pixels(to.1),
+            width
+        )
+        .unwrap();
+    }
+
+    fn draw_cages(&self, grid: &Grid, output: &mut String) {
+        for cage in &grid.constraints.cages {
+            for cell in &cage.cells {
+                let (x, y) = (cell.column(), cell.row());
+                let left = to_pixels(x) + CAGE_INSET;
+                let top = to_pixels(y) + CAGE_INSET;
+                let (right, bottom) = (
+                    left + CELL_SIZE - 2 * CAGE_INSET,
+                    top + CELL_SIZE - 2 * CAGE_INSET,
+                );
+                if is_outside(cage, x.checked_sub(1), Some(y)) {
+                    draw_dashed_line(output, (left, top), (left, bottom));
+                }
+                if is_outside(cage, Some(x + 1), Some(y)) {
+                    draw_dashed_line(output, (right, top), (right, bottom));
+                }
+                if is_outside(cage, Some(x), y.checked_sub(1)) {
+                    draw_dashed_line(output, (left, top), (right, top));
+                }
+                if is_outside(cage, Some(x), Some(y + 1)) {
+                    draw_dashed_line(output, (left, bottom), (right, bottom));
+                }
+            }
+            if let Some(first) = cage
+                .cells
+                .iter()
+                .min_by_key(|cell| (cell.row(), cell.column()))
+            {
+                writeln!(
+                    output,
+                    r#"<text x="{}" y="{}" font-family="sans-serif" font-size="9">{}</text>"#,
+                    to_pixels(first.column()) + CAGE_INSET + 1,
+                    to_pixels(first.row()) + CAGE_INSET + 9,
+                    cage.sum
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    fn draw_dots(&self, grid: &Grid, output: &mut String) {
+        for dot in &grid.constraints.dots {
+            let (first, second) = (&dot.first, &dot.second);
+            // The centre of the shared border is the midpoint between the centres of both cells.
+            let center_x = (to_pixels(first.column()) + to_pixels(second.column()) + CELL_SIZE) / 2;
+            let center_y = (to_pixels(first.row()) + to_pixels(second.row()) + CELL_SIZE) / 2;
+            let fill = match dot.kind {
+                DotKind::White => "white",
+                DotKind::Black => "black",
+            };
+            writeln!(
+                output,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{}" stroke="black"/>"#,
+                center_x, center_y, DOT_RADIUS, fill
+            )
+            .unwrap();
+        }
+    }
+
+    // Odd cells are marked with a circle and even cells with a square behind the digit.
+    fn draw_parities(&self, grid: &Grid, output: &mut String) {
+        for parity in &grid.constraints.parities {
+            let (x, y) = get_center(&parity.cell);
+            let radius = PARITY_RADIUS as f64;
+            match parity.kind {
+                ParityKind::Odd => writeln!(
+                    output,
+                    r#"<circle cx="{}" cy="{}" r="{}" fill="lightgray"/>"#,
+                    x, y, PARITY_RADIUS
+                ),
+                ParityKind::Even => writeln!(
+                    output,
+                    r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="lightgray"/>"#,
+                    x - radius,
+                    y - radius,
+                    2 * PARITY_RADIUS
+                ),
+            }
+            .unwrap();
+        }
+    }
+
+    // The chevron sits on the shared border with its tip towards the smaller cell.
+    fn draw_inequalities(&self, grid: &Grid, output: &mut String) {
+        for inequality in &grid.constraints.inequalities {
+            let (smaller, greater) = (
+                get_center(&inequality.smaller),
+                get_center(&inequality.greater),
+            );
+            let center = ((smaller.0 + greater.0) / 2.0, (smaller.1 + greater.1) / 2.0);
+            let tip = move_towards(center, smaller, CHEVRON_LENGTH / 2.0);
+            let back = move_towards(center, greater, CHEVRON_LENGTH / 2.0);
+            // The arms spread perpendicular to the direction between the cells.
+            let (dx, dy) = (
+                (smaller.1 - greater.1) / CELL_SIZE as f64,
+                (greater.0 - smaller.0) / CELL_SIZE as f64,
+            );
+            let arms = [-1.0, 1.0]
+                .iter()
+                .map(|side| {
+                    (
+                        back.0 + side * dx * CHEVRON_LENGTH,
+                        back.1 + side * dy * CHEVRON_LENGTH,
+                    )
+                })
+                .collect::<Vec<_>>();
+            draw_polyline(
+                output,
+                &[arms[0], tip, arms[1]],
+                r#"stroke="black" stroke-width="2""#,
+            );
+        }
+    }
+
+    // Only the four windows are shaded, the ghost windows follow from them.
+    fn draw_windows(&self, grid: &Grid, output: &mut String) {
+        let windows = grid.constraints.extra_units();
+        for window in windows.iter().filter(|unit| unit.kind == UnitKind::Window) {
+            let left = window.cells.iter().map(|cell| cell.column()).min().unwrap();
+            let top = window.cells.iter().map(|cell| cell.row()).min().unwrap();
+            writeln!(
+                output,
+                r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="gainsboro"/>"#,
+                to_pixels(left),
+                to_pixels(top),
+                SUBGRID_SIZE * CELL_SIZE
+            )
+            .unwrap();
+        }
+    }
+
+    fn draw_thermometers(&self, grid: &Grid, output: &mut String) {
+        for thermometer in &grid.constraints.thermometers {
+            let bulb = get_center(&thermometer.cells[0]);
+            writeln!(
+                output,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="lightgray"/>"#,
+                bulb.0, bulb.1, BULB_RADIUS
+            )
+            .unwrap();
+            let points = thermometer.cells.iter().map(get_center).collect::<Vec<_>>();
+            draw_polyline(
+                output,
+                &points,
+                r#"stroke="lightgray" stroke-width="10" stroke-linecap="round" stroke-linejoin="round""#,
+            );
+        }
+    }
+
+    // The arrow starts at the edge of its circle and ends with a head in the center of its last
+    // cell.
+    fn draw_arrows(&self, grid: &Grid, output: &mut String) {
+        for arrow in &grid.constraints.arrows {
+            let circle = get_center(&arrow.circle);
+            writeln!(
+                output,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="none" stroke="gray" stroke-width="2"/>"#,
+                circle.0, circle.1, CIRCLE_RADIUS
+            )
+            .unwrap();
+            let mut points = vec![circle];
+            points.extend(arrow.cells.iter().map(get_center));
+            let (start, next) = (points[0], points[1]);
+            points[0] = move_towards(start, next, CIRCLE_RADIUS as f64);
+            draw_polyline(
+                output,
+                &points,
+                r#"stroke="gray" stroke-width="2" stroke-linejoin="round""#,
+            );
+            let (end, previous) = (points[points.len() - 1], points[points.len() - 2]);
+            let angle = (end.1 - previous.1).atan2(end.0 - previous.0);
+            let head = [angle - 0.5, angle + 0.5]
+                .iter()
+                .map(|angle| {
+                    (
+                        end.0 - ARROW_HEAD_LENGTH * angle.cos(),
+                        end.1 - ARROW_HEAD_LENGTH * angle.sin(),
+                    )
+                })
+                .collect::<Vec<_>>();
+            draw_polyline(
+                output,
+                &[head[0], end, head[1]],
+                r#"stroke="gray" stroke-width="2""#,
+            );
+        }
+    }
+
+    fn draw_sandwiches(&self, grid: &Grid, output: &mut String) {
+        for sandwich in &grid.constraints.sandwiches {
+            let (x, y) = match sandwich.line {
+                Line::Row(y) => (SIZE + CLUE_SIZE / 2 - MARGIN, to_pixels(y) + CELL_SIZE / 2),
+                Line::Column(x) => (to_pixels(x) + CELL_SIZE / 2, SIZE + CLUE_SIZE / 2 - MARGIN),
+            };
+            writeln!(
+                output,
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="16" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                x, y, sandwich.sum
+            )
+            .unwrap();
+        }
+    }
+
+    fn draw_digits(&self, grid: &Grid, output: &mut String) {
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Known(value) = grid.get_digit(x, y) {
+                    writeln!(
+                        output,
+                        r#"<text x="{}" y="{}" font-family="sans-serif" font-size="24" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                        to_pixels(x) + CELL_SIZE / 2,
+                        to_pixels(y) + CELL_SIZE / 2,
+                        value
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+}
+
+fn to_pixels(coordinate: usize) -> usize {
+    MARGIN + coordinate * CELL_SIZE
+}
+
+fn get_center(cell: &Cell) -> (f64, f64) {
+    let half = CELL_SIZE as f64 / 2.0;
+    (
+        to_pixels(cell.column()) as f64 + half,
+        to_pixels(cell.row()) as f64 + half,
+    )
+}
+
+fn move_towards(from: (f64, f64), to: (f64, f64), distance: f64) -> (f64, f64) {
+    let length = (to.0 - from.0).hypot(to.1 - from.1);
+    (
+        from.0 + (to.0 - from.0) * distance / length,
+        from.1 + (to.1 - from.1) * distance / length,
+    )
+}
+
+fn draw_polyline(output: &mut String, points: &[(f64, f64)], style: &str) {
+    let points = points
+        .iter()
+        .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+        .collect::<Vec<_>>();
+    writeln!(
+        output,
+        r#"<polyline points="{}" fill="none" {}/>"#,
+        points.join(" "),
+        style
+    )
+    .unwrap();
+}
+
+fn is_outside(cage: &Cage, x: Option<usize>, y: Option<usize>) -> bool {
+    match (x, y) {
+        (Some(x), Some(y)) if x < GRID_JOINT_SIZE && y < GRID_JOINT_SIZE => !cage.contains(x, y),
+        _ => true,
+    }
+}
+
+fn draw_dashed_line(output: &mut String, from: (usize, usize), to: (usize, usize)) {
+    writeln!(
+        output,
+        r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-dasharray="3,3"/>"#,
+        from.0, from.1, to.0, to.1
+    )
+    .unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::parser::Parser;
+
+    use super::*;
+
+    type TestResult = Result<(), Box<dyn std::error::Error>>;
+
+    #[test]
+    fn test_grid_is_rendered_as_svg() -> TestResult {
+        let grid = Parser::new().parse(include_str!("test.grid").to_owned())?;
+        let output = SvgRenderer::new().render(&grid)?;
+        assert!(output.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert!(output.trim_end().ends_with("</svg>"));
+        assert!(output.contains(">1</text>"));
+        // Outer border and box borders are thick, the rest of the lines are thin.
+        let thick = output.matches("stroke-width=\"3\"").count();
+        assert_eq!(2 * 4 * GRID_JOINT_SIZE, thick);
+        Ok(())
+    }
+
+    #[test]
+    fn test_constraints_are_drawn() -> TestResult {
+        let syntax = format!(
+            "{}\ncage 17 r1c1 r1c2\ndot white r2c1 r2c2\ndot black r2c1 r3c1",
+            ".".repeat(81)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = SvgRenderer::new().render(&grid)?;
+        assert_eq!(6, output.matches("stroke-dasharray").count());
+        assert!(output.contains(">17</text>"));
+        assert!(output.contains(r#"<circle cx="44" cy="64" r="5" fill="white""#));
+        assert!(output.contains(r#"<circle cx="24" cy="84" r="5" fill="black""#));
+        let grid = Parser::new().parse_line(format!("{}\nwindows", ".".repeat(81)))?;
+        let output = SvgRenderer::new().render(&grid)?;
+        assert_eq!(4, output.matches("fill=\"gainsboro\"").count());
+        assert!(output.contains(r#"<rect x="44" y="44" width="120" height="120""#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_paths_and_sandwiches_are_drawn() -> TestResult {
+        let syntax = format!(
+            "{}\nthermometer r1c1 r1c2\narrow r2c1 r3c1\nsandwich row 1 12",
+            ".".repeat(81)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = SvgRenderer::new().render(&grid)?;
+        let size = SIZE + CLUE_SIZE;
+        assert!(output.contains(&format!(r#"width="{0}" height="{0}""#, size)));
+        assert!(output.contains(r#"<circle cx="24" cy="24" r="14" fill="lightgray"/>"#));
+        assert!(output.contains(r#"<polyline points="24.0,24.0 64.0,24.0""#));
+        assert!(output.contains(r#"<polyline points="24.0,80.0 24.0,104.0""#));
+        assert!(output.contains(r#"<polyline points="20.2,97.0 24.0,104.0 27.8,97.0""#));
+        assert!(output.contains(">12</text>"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parities_and_inequalities_are_drawn() -> TestResult {
+        let syntax = format!(
+            "{}\nodd r1c1\neven r1c2\ninequality r1c1 < r1c2\ninequality r2c1 > r1c1",
+            ".".repeat(81)
+        );
+        let grid = Parser::new().parse_line(syntax)?;
+        let output = SvgRenderer::new().render(&grid)?;
+        assert!(output.contains(r#"<circle cx="24" cy="24" r="15" fill="lightgray"/>"#));
+        assert!(output.contains(r#"<rect x="49" y="9" width="30" height="30" fill="lightgray"/>"#));
+        assert!(output.contains(r#"<polyline points="47.0,18.0 41.0,24.0 47.0,30.0""#));
+        assert!(output.contains(r#"<polyline points="30.0,47.0 24.0,41.0 18.0,47.0""#));
+        Ok(())
+    }
+}
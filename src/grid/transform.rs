@@ -0,0 +1,350 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+
+use crate::grid::cell::Cell;
+use crate::grid::digit::{Digit, UnknownDigit};
+use crate::grid::{Grid, GRID_CELLS, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_LENGTH, SUBGRID_SIZE};
+
+pub type TransformResult<T> = Result<T, TransformError>;
+pub type TransformError = Cow<'static, str>;
+
+// A validity-preserving symmetry of classic grids: a permutation of the cells followed by a
+// relabelling of the digits. Transformations are composed with `then`, so that a puzzle can be
+// disguised by a chain of them and restored by its inverse.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Transformation {
+    // The digit of the cell `sources[index]` moves to the cell `index`.
+    sources: Vec<usize>,
+    // The digit `value` is written as `labels[value]`, with the label of 0 unused.
+    labels: Vec<u32>,
+}
+
+impl Default for Transformation {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transformation {
+    pub fn identity() -> Self {
+        Self {
+            sources: (0..GRID_CELLS).collect(),
+            labels: (0..=SUBGRID_LENGTH as u32).collect(),
+        }
+    }
+
+    pub(crate) fn from_parts(sources: Vec<usize>, labels: Vec<u32>) -> Self {
+        Self { sources, labels }
+    }
+
+    // Rotates the grid by a quarter turn clockwise.
+    pub fn rotate() -> Self {
+        Self::from_sources(|x, y| (y, GRID_JOINT_SIZE - 1 - x))
+    }
+
+    // Mirrors the grid around its vertical axis.
+    pub fn reflect_horizontally() -> Self {
+        Self::from_sources(|x, y| (GRID_JOINT_SIZE - 1 - x, y))
+    }
+
+    // Mirrors the grid around its horizontal axis.
+    pub fn reflect_vertically() -> Self {
+        Self::from_sources(|x, y| (x, GRID_JOINT_SIZE - 1 - y))
+    }
+
+    // Mirrors the grid around its main diagonal.
+    pub fn transpose() -> Self {
+        Self::from_sources(|x, y| (y, x))
+    }
+
+    // Writes the digit `value` as `labels[value - 1]`.
+    pub fn relabel(labels: &[u32]) -> TransformResult<Self> {
+        let mut sorted = labels.to_vec();
+        sorted.sort_unstable();
+        if !sorted.iter().copied().eq(1..=SUBGRID_LENGTH as u32) {
+            let message = format!("Labels should be a permutation of 1 to {}", SUBGRID_LENGTH);
+            return Err(message.into());
+        }
+        let mut transformation = Self::identity();
+        transformation.labels[1..].copy_from_slice(labels);
+        Ok(transformation)
+    }
+
+    pub fn swap_rows(first: usize, second: usize) -> TransformResult<Self> {
+        validate_lines(first, second, "Rows", "band")?;
+        Ok(Self::from_sources(|x, y| (x, swap(y, first, second))))
+    }
+
+    pub fn swap_columns(first: usize, second: usize) -> TransformResult<Self> {
+        validate_lines(first, second, "Columns", "stack")?;
+        Ok(Self::from_sources(|x, y| (swap(x, first, second), y)))
+    }
+
+    // Swaps the horizontal bands of subgrids, keeping the order of the rows within them.
+    pub fn swap_bands(first: usize, second: usize) -> TransformResult<Self> {
+        validate_bands(first, second, "Bands")?;
+        Ok(Self::from_sources(|x, y| (x, swap_band(y, first, second))))
+    }
+
+    // Swaps the vertical stacks of subgrids, keeping the order of the columns within them.
+    pub fn swap_stacks(first: usize, second: usize) -> TransformResult<Self> {
+        validate_bands(first, second, "Stacks")?;
+        Ok(Self::from_sources(|x, y| (swap_band(x, first, second), y)))
+    }
+
+    // The transformation applying this one and then the other.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            sources: other
+                .sources
+                .iter()
+                .map(|source| self.sources[*source])
+                .collect(),
+            labels: self
+                .labels
+                .iter()
+                .map(|label| other.labels[*label as usize])
+                .collect(),
+        }
+    }
+
+    pub fn inverse(&self) -> Self {
+        let mut inverse = Self::identity();
+        for (target, source) in self.sources.iter().enumerate() {
+            inverse.sources[*source] = target;
+        }
+        for (value, label) in self.labels.iter().enumerate() {
+            inverse.labels[*label as usize] = value as u32;
+        }
+        inverse
+    }
+
+    pub fn get_source(&self, cell: Cell) -> Cell {
+        Cell::from_index(self.sources[cell.index()])
+    }
+
+    pub fn get_label(&self, value: u32) -> u32 {
+        self.labels[value as usize]
+    }
+
+    // Moves and relabels the known digits and the candidates of the cells. Variant constraints are
+    // tied to the cells they cover, so only classic grids are transformed.
+    pub fn apply(&self, grid: &Grid) -> TransformResult<Grid> {
+        if !grid.constraints.is_classic() {
+            return Err("Only grids without variant constraints can be transformed".into());
+        }
+        let digits = Cell::all()
+            .map(|cell| self.relabel_digit(grid.get_cell(self.get_source(cell))))
+            .collect();
+        Ok(Grid::from_cells(digits, grid.constraints.clone()))
+    }
+
+    fn relabel_digit(&self, digit: &Digit) -> Digit {
+        match digit {
+            Digit::Known(value) => Digit::Known(self.get_label(*value)),
+            Digit::Unknown(unknown) => {
+                let mut possible_values = unknown
+                    .possible_values
+                    .iter()
+                    .map(|value| self.get_label(*value))
+                    .collect::<Vec<_>>();
+                possible_values.sort_unstable();
+                Digit::Unknown(UnknownDigit::new(possible_values))
+            }
+        }
+    }
+
+    fn from_sources<F>(source: F) -> Self
+    where
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let sources = Cell::all()
+            .map(|cell| {
+                let (x, y) = source(cell.column(), cell.row());
+                Cell::new(x, y).index()
+            })
+            .collect();
+        Self {
+            sources,
+            ..Self::identity()
+        }
+    }
+}
+
+fn validate_lines(first: usize, second: usize, name: &str, group: &str) -> TransformResult<()> {
+    if first >= GRID_JOINT_SIZE || second >= GRID_JOINT_SIZE {
+        let message = format!("{} should be between 1 and {}", name, GRID_JOINT_SIZE);
+        return Err(message.into());
+    }
+    if first / SUBGRID_SIZE != second / SUBGRID_SIZE {
+        let message = format!(
+            "{} {} and {} are not in the same {}",
+            name,
+            first + 1,
+            second + 1,
+            group
+        );
+        return Err(message.into());
+    }
+    Ok(())
+}
+
+fn validate_bands(first: usize, second: usize, name: &str) -> TransformResult<()> {
+    if first >= GRID_SIZE || second >= GRID_SIZE {
+        let message = format!("{} should be between 1 and {}", name, GRID_SIZE);
+        return Err(message.into());
+    }
+    Ok(())
+}
+
+fn swap(line: usize, first: usize, second: usize) -> usize {
+    if line == first {
+        second
+    } else if line == second {
+        first
+    } else {
+        line
+    }
+}
+
+fn swap_band(line: usize, first: usize, second: usize) -> usize {
+    let band = swap(line / SUBGRID_SIZE, first, second);
+    band * SUBGRID_SIZE + line % SUBGRID_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::constraints::Constraints;
+    use crate::grid::parser::Parser;
+
+    use super::*;
+
+    const SOLUTION: &str =
+        "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+
+    fn parse(syntax: &str) -> Grid {
+        Parser::new().parse_line(syntax.to_owned()).unwrap()
+    }
+
+    fn get_transformations() -> Vec<Transformation> {
+        vec![
+            Transformation::rotate(),
+            Transformation::reflect_horizontally(),
+            Transformation::reflect_vertically(),
+            Transformation::transpose(),
+            Transformation::relabel(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap(),
+            Transformation::swap_rows(3, 5).unwrap(),
+            Transformation::swap_columns(7, 6).unwrap(),
+            Transformation::swap_bands(0, 2).unwrap(),
+            Transformation::swap_stacks(1, 0).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_rotation_moves_cells_clockwise() -> TransformResult<()> {
+        let grid = parse(SOLUTION);
+        let rotated = Transformation::rotate().apply(&grid)?;
+        assert_eq!(grid.get_digit(0, 8), rotated.get_digit(0, 0));
+        assert_eq!(grid.get_digit(0, 0), rotated.get_digit(8, 0));
+        assert_eq!(grid.get_digit(8, 0), rotated.get_digit(8, 8));
+        assert_eq!(grid.get_digit(3, 1), rotated.get_digit(7, 3));
+        let rotation = Transformation::transpose().then(&Transformation::reflect_horizontally());
+        assert_eq!(Transformation::rotate(), rotation);
+        let full_turn = (0..4).fold(Transformation::identity(), |transformation, _| {
+            transformation.then(&Transformation::rotate())
+        });
+        assert_eq!(Transformation::identity(), full_turn);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transformations_keep_solutions_valid() -> TransformResult<()> {
+        let grid = parse(SOLUTION);
+        for transformation in get_transformations() {
+            let transformed = transformation.apply(&grid)?;
+            assert!(transformed.is_solved_correctly(), "{:?}", transformation);
+            assert_ne!(grid, transformed);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_composed_transformations_are_inverted() -> TransformResult<()> {
+        let grid = parse(SOLUTION);
+        let composed = get_transformations()
+            .iter()
+            .fold(Transformation::identity(), |composed, transformation| {
+                composed.then(transformation)
+            });
+        let step_by_step = get_transformations()
+            .iter()
+            .try_fold(grid.clone(), |grid, transformation| {
+                transformation.apply(&grid)
+            })?;
+        let transformed = composed.apply(&grid)?;
+        assert_eq!(step_by_step, transformed);
+        assert_eq!(grid, composed.inverse().apply(&transformed)?);
+        assert_eq!(
+            Transformation::identity(),
+            composed.then(&composed.inverse())
+        );
+        assert_eq!(
+            Transformation::identity(),
+            composed.inverse().then(&composed)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_candidates_are_transformed() -> TransformResult<()> {
+        let mut grid = Grid::default();
+        grid.set_digit(1, 0, Digit::Known(4));
+        grid.set_digit(2, 0, Digit::Unknown(UnknownDigit::new(vec![1, 2, 5])));
+        let relabelling = Transformation::relabel(&[3, 1, 2, 9, 8, 7, 6, 5, 4])?;
+        let transformation = Transformation::swap_columns(1, 2)?.then(&relabelling);
+        let transformed = transformation.apply(&grid)?;
+        assert_eq!(&Digit::Known(9), transformed.get_digit(2, 0));
+        let candidates = Digit::Unknown(UnknownDigit::new(vec![1, 3, 8]));
+        assert_eq!(&candidates, transformed.get_digit(1, 0));
+        assert_eq!(grid, transformation.inverse().apply(&transformed)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_transformations_are_rejected() {
+        assert!(Transformation::swap_rows(2, 3).is_err());
+        assert!(Transformation::swap_columns(0, 9).is_err());
+        assert!(Transformation::swap_bands(0, 3).is_err());
+        assert!(Transformation::relabel(&[1, 2, 3, 4, 5, 6, 7, 8, 8]).is_err());
+        assert!(Transformation::relabel(&[1, 2, 3]).is_err());
+        let constraints = Constraints {
+            diagonal: true,
+            ..Constraints::default()
+        };
+        let grid = Grid::from_cells(parse(SOLUTION).digits, constraints);
+        assert!(Transformation::rotate().apply(&grid).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::slice::Iter;
+
+use crate::grid::cell::Cell;
+use crate::grid::constraints::Unit;
+use crate::grid::GRID_CELLS;
+
+// The all-different units of a grid together with an index of the units containing each cell, so
+// that the strategies can be written once for rows, columns, subgrids and the extra units.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Units {
+    pub units: Vec<Unit>,
+    cell_units: Vec<Vec<usize>>,
+}
+
+impl Units {
+    pub fn new(units: Vec<Unit>) -> Self {
+        let mut cell_units = vec![Vec::new(); GRID_CELLS];
+        for (index, unit) in units.iter().enumerate() {
+            for cell in &unit.cells {
+                cell_units[cell.index()].push(index);
+            }
+        }
+        Self { units, cell_units }
+    }
+
+    pub fn iter(&self) -> Iter<'_, Unit> {
+        self.units.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    // Indices of the units containing the cell, in the order of the units.
+    pub fn get_cell_units(&self, x_joint: usize, y_joint: usize) -> &[usize] {
+        self.get_units_of(Cell::new(x_joint, y_joint))
+    }
+
+    pub fn get_units_of(&self, cell: Cell) -> &[usize] {
+        &self.cell_units[cell.index()]
+    }
+
+    // Indices of the units containing all the cells.
+    pub fn get_common_units<'a>(&'a self, cells: &'a [&Cell]) -> impl Iterator<Item = usize> + 'a {
+        let first = match cells.first() {
+            Some(cell) => self.get_cell_units(cell.column(), cell.row()),
+            None => &[],
+        };
+        first.iter().copied().filter(move |unit| {
+            cells[1..].iter().all(|cell| {
+                self.get_cell_units(cell.column(), cell.row())
+                    .contains(unit)
+            })
+        })
+    }
+
+    // Other cells sharing a unit with the cell, each of them once and in reading order.
+    pub fn get_peers(&self, x_joint: usize, y_joint: usize) -> Vec<Cell> {
+        let mut peers = self
+            .get_cell_units(x_joint, y_joint)
+            .iter()
+            .flat_map(|unit| &self.units[*unit].cells)
+            .filter(|cell| cell.column() != x_joint || cell.row() != y_joint)
+            .cloned()
+            .collect::<Vec<_>>();
+        peers.sort_unstable_by_key(|cell| (cell.row(), cell.column()));
+        peers.dedup();
+        peers
+    }
+}
+
+impl<'a> IntoIterator for &'a Units {
+    type Item = &'a Unit;
+    type IntoIter = Iter<'a, Unit>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.units.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::constraints::{Constraints, UnitKind};
+    use crate::grid::Grid;
+
+    use super::*;
+
+    #[test]
+    fn test_cells_are_indexed_by_units() {
+        let units = Grid::default().get_units();
+        assert_eq!(27, units.len());
+        let kinds = units
+            .get_cell_units(4, 7)
+            .iter()
+            .map(|unit| (units.units[*unit].kind, units.units[*unit].index))
+            .collect::<Vec<_>>();
+        let expected = vec![
+            (UnitKind::Row, 7),
+            (UnitKind::Column, 4),
+            (UnitKind::Subgrid, 7),
+        ];
+        assert_eq!(expected, kinds);
+        assert_eq!(20, units.get_peers(4, 7).len());
+        let cells = [&Cell::new(3, 6), &Cell::new(5, 6)];
+        assert_eq!(
+            vec![6, 25],
+            units.get_common_units(&cells).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_extra_units_are_indexed() {
+        let constraints = Constraints {
+            diagonal: true,
+            ..Constraints::default()
+        };
+        let grid = Grid::from_cells(Grid::default().digits, constraints);
+        let units = grid.get_units();
+        assert_eq!(29, units.len());
+        assert_eq!(5, units.get_cell_units(4, 4).len());
+        assert_eq!(3, units.get_cell_units(3, 4).len());
+        // The centre sees the whole of both diagonals besides its row, column and subgrid.
+        assert_eq!(32, units.get_peers(4, 4).len());
+    }
+}
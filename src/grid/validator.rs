@@ -0,0 +1,602 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+use crate::grid::cell::Cell;
+use crate::grid::constraints::get_adjacent_cells;
+pub use crate::grid::constraints::UnitKind;
+use crate::grid::digit::{Digit, LocalizedDigit};
+use crate::grid::units::Units;
+use crate::grid::visibility::Visibility;
+use crate::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Conflict {
+    pub unit_kind: UnitKind,
+    pub unit_index: usize,
+    pub digit: u32,
+    pub cells: Vec<Cell>,
+}
+
+impl Conflict {
+    pub fn new(unit_kind: UnitKind, unit_index: usize, digit: u32, cells: Vec<Cell>) -> Self {
+        Self {
+            unit_kind,
+            unit_index,
+            digit,
+            cells,
+        }
+    }
+}
+
+impl Display for Conflict {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self.unit_kind {
+            UnitKind::Relation(relation) => write!(
+                formatter,
+                "Digit {} is repeated {} at",
+                self.digit,
+                relation.description()
+            )?,
+            _ => write!(
+                formatter,
+                "Digit {} is repeated in {} {} at",
+                self.digit,
+                self.unit_kind,
+                self.unit_index + 1
+            )?,
+        }
+        for (index, cell) in self.cells.iter().enumerate() {
+            let separator = if index == 0 { " " } else { ", " };
+            write!(
+                formatter,
+                "{}r{}c{}",
+                separator,
+                cell.row() + 1,
+                cell.column() + 1
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    pub conflicts: Vec<Conflict>,
+    pub empty_candidate_cells: Vec<Cell>,
+    pub unsatisfied_cages: Vec<usize>,
+    pub broken_pairs: Vec<(Cell, Cell)>,
+    // Each broken cell constraint is given by its name and its index among the constraints with
+    // the same name.
+    pub unsatisfied_constraints: Vec<(&'static str, usize)>,
+}
+
+impl ValidationReport {
+    pub fn new(
+        conflicts: Vec<Conflict>,
+        empty_candidate_cells: Vec<Cell>,
+        unsatisfied_cages: Vec<usize>,
+        broken_pairs: Vec<(Cell, Cell)>,
+        unsatisfied_constraints: Vec<(&'static str, usize)>,
+    ) -> Self {
+        Self {
+            conflicts,
+            empty_candidate_cells,
+            unsatisfied_cages,
+            broken_pairs,
+            unsatisfied_constraints,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.conflicts.is_empty()
+            && self.empty_candidate_cells.is_empty()
+            && self.unsatisfied_cages.is_empty()
+            && self.broken_pairs.is_empty()
+            && self.unsatisfied_constraints.is_empty()
+    }
+}
+
+impl Display for ValidationReport {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        for conflict in &self.conflicts {
+            writeln!(formatter, "{}", conflict)?;
+        }
+        for cell in &self.empty_candidate_cells {
+            writeln!(
+                formatter,
+                "Cell r{}c{} has no possible values",
+                cell.row() + 1,
+                cell.column() + 1
+            )?;
+        }
+        for index in &self.unsatisfied_cages {
+            writeln!(formatter, "Cage {} cannot add up to its sum", index + 1)?;
+        }
+        for (first, second) in &self.broken_pairs {
+            writeln!(
+                formatter,
+                "Digits at r{}c{} and r{}c{} break the constraint between them",
+                first.row() + 1,
+                first.column() + 1,
+                second.row() + 1,
+                second.column() + 1
+            )?;
+        }
+        for (name, index) in &self.unsatisfied_constraints {
+            let mut characters = name.chars();
+            let first = characters.next().unwrap().to_ascii_uppercase();
+            let name = first.to_string() + characters.as_str();
+            writeln!(formatter, "{} {} cannot be satisfied", name, index + 1)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Validator<'a> {
+    grid: &'a Grid,
+}
+
+impl<'a> Validator<'a> {
+    pub fn new(grid: &'a Grid) -> Self {
+        Self { grid }
+    }
+
+    pub fn validate(&self) -> ValidationReport {
+        let units = self.grid.get_units();
+        let mut conflicts = Vec::new();
+        for unit in &units {
+            let digits = unit
+                .cells
+                .iter()
+                .map(|cell| {
+                    let digit = self.grid.get_digit(cell.column(), cell.row());
+                    LocalizedDigit::from_borrowed(digit, cell.column(), cell.row())
+                })
+                .collect::<Vec<_>>();
+            Self::find_conflicts(unit.kind, unit.index, &digits, &mut conflicts);
+        }
+        self.find_relation_conflicts(&mut conflicts);
+        let empty_candidate_cells = self.find_empty_candidate_cells(&units);
+        let unsatisfied_cages = self.find_unsatisfied_cages();
+        let broken_pairs = self.find_broken_pairs();
+        let unsatisfied_constraints = self.find_unsatisfied_constraints();
+        ValidationReport::new(
+            conflicts,
+            empty_candidate_cells,
+            unsatisfied_cages,
+            broken_pairs,
+            unsatisfied_constraints,
+        )
+    }
+
+    fn find_unsatisfied_constraints(&self) -> Vec<(&'static str, usize)> {
+        let values = self.grid.get_values();
+        let constraints = self
+            .grid
+            .constraints
+            .get_cell_constraints()
+            .collect::<Vec<_>>();
+        let mut unsatisfied = Vec::new();
+        for (position, constraint) in constraints.iter().enumerate() {
+            if !constraint.is_feasible(&values) {
+                let name = constraint.name();
+                let index = constraints[..position]
+                    .iter()
+                    .filter(|other| other.name() == name)
+                    .count();
+                unsatisfied.push((name, index));
+            }
+        }
+        unsatisfied
+    }
+
+    fn find_broken_pairs(&self) -> Vec<(Cell, Cell)> {
+        let constraints = &self.grid.constraints;
+        if !constraints.has_pair_constraints() {
+            return Vec::new();
+        }
+        let mut pairs = Vec::new();
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                let cell = Cell::new(x, y);
+                for other in get_adjacent_cells(x, y)
+                    .into_iter()
+                    .filter(|other| *other > cell)
+                {
+                    let digits = (
+                        self.grid.get_digit(x, y),
+                        self.grid.get_digit(other.column(), other.row()),
+                    );
+                    if let (Digit::Known(value), Digit::Known(other_value)) = digits {
+                        if !constraints.allows_pair(&cell, &other, *value, *other_value) {
+                            pairs.push((cell, other));
+                        }
+                    }
+                }
+            }
+        }
+        pairs
+    }
+
+    // Every pair of cells seeing each other is reported as a separate conflict.
+    fn find_relation_conflicts(&self, conflicts: &mut Vec<Conflict>) {
+        for (index, relation) in self.grid.constraints.relations.iter().enumerate() {
+            for y in 0..GRID_JOINT_SIZE {
+                for x in 0..GRID_JOINT_SIZE {
+                    let value = match self.grid.get_digit(x, y) {
+                        Digit::Known(value) => *value,
+                        Digit::Unknown(_) => continue,
+                    };
+                    let cell = Cell::new(x, y);
+                    for other in relation.get_visible_cells(x, y) {
+                        if other > cell && self.grid.get_cell(other) == &Digit::Known(value) {
+                            let cells = vec![cell, other];
+                            let kind = UnitKind::Relation(*relation);
+                            conflicts.push(Conflict::new(kind, index, value, cells));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn find_unsatisfied_cages(&self) -> Vec<usize> {
+        let cages = &self.grid.constraints.cages;
+        if cages.is_empty() {
+            return Vec::new();
+        }
+        let values = self.grid.get_values();
+        (0..cages.len())
+            .filter(|index| !cages[*index].is_satisfied_by(&values))
+            .collect()
+    }
+
+    fn find_conflicts(
+        unit_kind: UnitKind,
+        unit_index: usize,
+        digits: &[LocalizedDigit],
+        conflicts: &mut Vec<Conflict>,
+    ) {
+        for value in 1..=SUBGRID_LENGTH as u32 {
+            let cells = digits
+                .iter()
+                .filter(|digit| *digit.digit == Digit::Known(value))
+                .map(|digit| Cell::new(digit.x, digit.y))
+                .collect::<Vec<_>>();
+            if cells.len() > 1 {
+                let conflict = Conflict::new(unit_kind, unit_index, value, cells);
+                conflicts.push(conflict);
+            }
+        }
+    }
+
+    // Besides the units, the values are checked against the cages, the dots and the cell
+    // constraints together with the digits already placed.
+    fn find_empty_candidate_cells(&self, units: &Units) -> Vec<Cell> {
+        let values = self.grid.get_values();
+        let constraints = &self.grid.constraints;
+        let mut cells = Vec::new();
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Unknown(_) = self.grid.get_digit(x, y) {
+                    let cell = Cell::new(x, y);
+                    let neighbours = self.grid.get_unit_neighbour_digits(units, x, y);
+                    let has_candidate = (1..=SUBGRID_LENGTH as u32).any(|value| {
+                        !neighbours.contains(&&Digit::Known(value))
+                            && constraints.allows(&values, cell.index(), value)
+                    });
+                    if !has_candidate {
+                        cells.push(cell);
+                    }
+                }
+            }
+        }
+        cells
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::cell_constraint::{Arrow, Line, Sandwich, Thermometer};
+    use crate::grid::constraints::{Cage, Dot, DotKind, Regions};
+    use crate::grid::visibility::Relation;
+    use crate::grid::Subgrid;
+
+    use super::*;
+
+    #[test]
+    fn test_valid_partial_grid_has_no_conflicts() {
+        let grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            Subgrid::from_digits(vec![4, 5, 6, 7, 8, 9, 1, 2, 3]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let report = grid.validate();
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn test_diagonal_conflicts_are_reported() {
+        let mut grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::from_digits(vec![0, 0, 0, 0, 1, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        assert!(grid.validate().is_valid());
+        grid.constraints.diagonal = true;
+        let expected = vec![Conflict::new(
+            UnitKind::Diagonal,
+            0,
+            1,
+            vec![Cell::new(0, 0), Cell::new(4, 4)],
+        )];
+        let report = grid.validate();
+        assert_eq!(expected, report.conflicts);
+        assert_eq!(
+            "Digit 1 is repeated in diagonal 1 at r1c1, r5c5\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn test_regions_replace_subgrids() {
+        let mut grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::from_digits(vec![0, 1, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::from_digits(vec![0, 0, 0, 0, 1, 0, 0, 0, 0]),
+        ]);
+        grid.set_digit(1, 1, Digit::Known(1));
+        let conflicts = grid.validate().conflicts;
+        assert_eq!(2, conflicts.len());
+        assert_eq!(UnitKind::Subgrid, conflicts[1].unit_kind);
+        // Every column is a region, so the subgrids may repeat digits.
+        let regions = (0..GRID_JOINT_SIZE * GRID_JOINT_SIZE)
+            .map(|cell| cell % GRID_JOINT_SIZE)
+            .collect();
+        grid.constraints.regions = Some(Regions::new(regions));
+        let cells = vec![Cell::new(1, 1), Cell::new(1, 3)];
+        let expected = vec![
+            Conflict::new(UnitKind::Column, 1, 1, cells.clone()),
+            Conflict::new(UnitKind::Region, 1, 1, cells),
+        ];
+        assert_eq!(expected, grid.validate().conflicts);
+    }
+
+    #[test]
+    fn test_cells_emptied_by_cages_and_dots_are_reported() {
+        let mut grid = Grid::default();
+        grid.set_digit(0, 0, Digit::Known(1));
+        grid.set_digit(1, 5, Digit::Known(2));
+        assert!(grid.validate().is_valid());
+        // Only 2 completes the cage, but the column already holds it.
+        let cage = Cage::new(3, vec![Cell::new(0, 0), Cell::new(1, 0)]);
+        grid.constraints.cages.push(cage);
+        let report = grid.validate();
+        assert!(report.unsatisfied_cages.is_empty());
+        assert_eq!(vec![Cell::new(1, 0)], report.empty_candidate_cells);
+        let mut grid = Grid::default();
+        grid.set_digit(4, 4, Digit::Known(5));
+        grid.set_digit(5, 0, Digit::Known(4));
+        grid.set_digit(5, 8, Digit::Known(6));
+        assert!(grid.validate().is_valid());
+        // The white dot only allows 4 and 6 next to 5, and the column holds both.
+        let dot = Dot::new(DotKind::White, Cell::new(4, 4), Cell::new(5, 4));
+        grid.constraints.dots.push(dot);
+        let report = grid.validate();
+        assert!(report.broken_pairs.is_empty());
+        assert_eq!(vec![Cell::new(5, 4)], report.empty_candidate_cells);
+        assert_eq!("Cell r5c6 has no possible values\n", report.to_string());
+    }
+
+    #[test]
+    fn test_relation_conflicts_are_reported() {
+        let mut grid = Grid::default();
+        grid.set_digit(2, 0, Digit::Known(5));
+        grid.set_digit(3, 2, Digit::Known(5));
+        grid.set_digit(5, 5, Digit::Known(3));
+        grid.set_digit(6, 6, Digit::Known(3));
+        assert!(grid.validate().is_valid());
+        grid.constraints.relations = vec![Relation::AntiKnight];
+        let report = grid.validate();
+        assert_eq!(
+            "Digit 5 is repeated a knight's move apart at r1c3, r3c4\n",
+            report.to_string()
+        );
+        assert!(!grid.is_solved_correctly());
+        assert!(grid.get_neighbour_digits(3, 2).contains(&&Digit::Known(5)));
+        grid.constraints.relations.push(Relation::AntiKing);
+        let kinds = grid
+            .validate()
+            .conflicts
+            .into_iter()
+            .map(|conflict| conflict.unit_kind)
+            .collect::<Vec<_>>();
+        let expected = vec![
+            UnitKind::Relation(Relation::AntiKnight),
+            UnitKind::Relation(Relation::AntiKing),
+        ];
+        assert_eq!(expected, kinds);
+    }
+
+    #[test]
+    fn test_unsatisfied_cages_are_reported() {
+        let mut grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 2, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let cells = vec![Cell::new(0, 0), Cell::new(1, 0), Cell::new(2, 0)];
+        grid.constraints.cages.push(Cage::new(6, cells.clone()));
+        assert!(grid.validate().is_valid());
+        grid.constraints.cages[0] = Cage::new(13, cells);
+        let report = grid.validate();
+        assert_eq!(vec![0], report.unsatisfied_cages);
+        // No value completes the cage, so its empty cell has no possible values either.
+        assert_eq!(vec![Cell::new(2, 0)], report.empty_candidate_cells);
+        assert_eq!(
+            "Cell r1c3 has no possible values\nCage 1 cannot add up to its sum\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn test_broken_pairs_are_reported() {
+        let mut grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 2, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let (first, second) = (Cell::new(0, 0), Cell::new(1, 0));
+        let dot = Dot::new(DotKind::Black, first, second);
+        grid.constraints.dots.push(dot);
+        assert!(grid.validate().is_valid());
+        grid.constraints.dots[0].kind = DotKind::White;
+        assert!(grid.validate().is_valid());
+        grid.constraints.non_consecutive = true;
+        let report = grid.validate();
+        assert_eq!(vec![(first, second)], report.broken_pairs);
+        assert_eq!(
+            "Digits at r1c1 and r1c2 break the constraint between them\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn test_unsatisfied_cell_constraints_are_reported() {
+        let mut grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 2, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let cells = vec![Cell::new(0, 0), Cell::new(1, 0)];
+        grid.constraints
+            .thermometers
+            .push(Thermometer::new(cells.clone()));
+        let reversed = cells.into_iter().rev().collect();
+        grid.constraints
+            .thermometers
+            .push(Thermometer::new(reversed));
+        let arrow = Arrow::new(Cell::new(1, 0), vec![Cell::new(0, 0)]);
+        grid.constraints.arrows.push(arrow);
+        grid.constraints
+            .sandwiches
+            .push(Sandwich::new(Line::Row(0), 2));
+        let report = grid.validate();
+        let expected = vec![("thermometer", 1), ("arrow", 0)];
+        assert_eq!(expected, report.unsatisfied_constraints);
+        assert_eq!(
+            "Thermometer 2 cannot be satisfied\nArrow 1 cannot be satisfied\n",
+            report.to_string()
+        );
+    }
+
+    #[test]
+    fn test_all_conflicts_are_reported() {
+        let grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            Subgrid::from_digits(vec![9, 8, 7, 6, 5, 4, 3, 2, 1]),
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 9]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::from_digits(vec![0, 0, 0, 0, 0, 0, 0, 0, 9]),
+        ]);
+        let expected = vec![
+            Conflict::new(UnitKind::Row, 0, 1, vec![Cell::new(0, 0), Cell::new(6, 0)]),
+            Conflict::new(UnitKind::Row, 1, 4, vec![Cell::new(0, 1), Cell::new(5, 1)]),
+            Conflict::new(UnitKind::Row, 1, 5, vec![Cell::new(1, 1), Cell::new(4, 1)]),
+            Conflict::new(UnitKind::Row, 1, 6, vec![Cell::new(2, 1), Cell::new(3, 1)]),
+            Conflict::new(UnitKind::Row, 2, 9, vec![Cell::new(2, 2), Cell::new(8, 2)]),
+            Conflict::new(
+                UnitKind::Column,
+                8,
+                9,
+                vec![Cell::new(8, 2), Cell::new(8, 8)],
+            ),
+        ];
+        let report = grid.validate();
+        assert_eq!(expected, report.conflicts);
+        assert!(report.empty_candidate_cells.is_empty());
+    }
+
+    #[test]
+    fn test_cell_without_candidates_is_reported() {
+        let grid = Grid::new(vec![
+            Subgrid::from_digits(vec![0, 2, 3, 4, 5, 6, 7, 8, 0]),
+            Subgrid::from_digits(vec![0, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::from_digits(vec![9, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let report = grid.validate();
+        assert!(report.conflicts.is_empty());
+        assert_eq!(vec![Cell::new(0, 0)], report.empty_candidate_cells);
+        assert!(!report.is_valid());
+    }
+}
@@ -0,0 +1,115 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::cell::Cell;
+use crate::grid::GRID_JOINT_SIZE;
+
+// Cells that see each other must not hold the same digit. Rows, columns and regions are handled as
+// units, this covers relations spanning the whole grid, like the anti-knight rule.
+pub trait Visibility {
+    fn get_visible_cells(&self, x_joint: usize, y_joint: usize) -> Vec<Cell>;
+
+    fn sees(&self, first: &Cell, second: &Cell) -> bool {
+        self.get_visible_cells(first.column(), first.row())
+            .contains(second)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Relation {
+    AntiKnight,
+    AntiKing,
+}
+
+impl Relation {
+    pub const ALL: [Relation; 2] = [Relation::AntiKnight, Relation::AntiKing];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Relation::AntiKnight => "anti-knight",
+            Relation::AntiKing => "anti-king",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|relation| relation.name() == name)
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Relation::AntiKnight => "a knight's move apart",
+            Relation::AntiKing => "in diagonally adjacent cells",
+        }
+    }
+
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Relation::AntiKnight => &[
+                (-2, -1),
+                (-1, -2),
+                (1, -2),
+                (2, -1),
+                (2, 1),
+                (1, 2),
+                (-1, 2),
+                (-2, 1),
+            ],
+            // Orthogonally adjacent cells already share a row or a column.
+            Relation::AntiKing => &[(-1, -1), (1, -1), (1, 1), (-1, 1)],
+        }
+    }
+}
+
+impl Visibility for Relation {
+    fn get_visible_cells(&self, x_joint: usize, y_joint: usize) -> Vec<Cell> {
+        let size = GRID_JOINT_SIZE as isize;
+        self.offsets()
+            .iter()
+            .map(|(x_offset, y_offset)| (x_joint as isize + x_offset, y_joint as isize + y_offset))
+            .filter(|(x, y)| (0..size).contains(x) && (0..size).contains(y))
+            .map(|(x, y)| Cell::new(x as usize, y as usize))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_visible_cells_stay_inside_grid() {
+        assert_eq!(2, Relation::AntiKnight.get_visible_cells(0, 0).len());
+        assert_eq!(8, Relation::AntiKnight.get_visible_cells(4, 4).len());
+        assert_eq!(1, Relation::AntiKing.get_visible_cells(8, 8).len());
+        assert_eq!(4, Relation::AntiKing.get_visible_cells(4, 4).len());
+        let center = Cell::new(4, 4);
+        assert!(Relation::AntiKnight.sees(&center, &Cell::new(5, 6)));
+        assert!(!Relation::AntiKnight.sees(&center, &Cell::new(5, 5)));
+        assert!(Relation::AntiKing.sees(&center, &Cell::new(5, 5)));
+        assert!(!Relation::AntiKing.sees(&center, &Cell::new(4, 5)));
+    }
+}
@@ -22,5 +22,6 @@
  * SOFTWARE.
  */
 
+pub mod game;
 pub mod grid;
 pub mod solver;
@@ -22,34 +22,13 @@
  * SOFTWARE.
  */
 
-use std::io;
-use std::io::{Error, Read};
+use std::env;
+use std::process;
 
-use sudoku_solver::grid::generator::Generator;
-use sudoku_solver::grid::parser::Parser;
-use sudoku_solver::solver::Solver;
+mod cli;
 
 fn main() {
-    let mut stdin = io::stdin();
-    let grid_syntax = read_input(&mut stdin).expect("Cannot read from std input");
-    let parser = Parser::default();
-    let grid = parser.parse(grid_syntax).expect("Cannot parse grid syntax");
-    let solver = Solver::new(grid);
-    let solved_grid = solver.solve().expect("Cannot solve the given grid");
-    let generator = Generator::default();
-    let readable_grid = generator
-        .generate(&solved_grid)
-        .expect("Cannot generate readable grid");
-    println!("{}", readable_grid);
-    if solved_grid.is_solved_correctly() {
-        println!("Grid has been solved correctly.");
-    } else {
-        println!("Grid has been solved incorrectly!");
-    }
-}
-
-fn read_input(stdin: &mut dyn Read) -> Result<String, Error> {
-    let mut result = String::new();
-    stdin.read_to_string(&mut result)?;
-    Ok(result)
+    let arguments = env::args().skip(1).collect();
+    let exit_code = cli::run(arguments);
+    process::exit(exit_code as i32);
 }
@@ -0,0 +1,191 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::grid::Grid;
+use crate::solver::backtracking::BacktrackingSolver;
+use crate::solver::cancellation::CancellationToken;
+use crate::solver::config::SolverConfig;
+use crate::solver::dlx::DancingLinksSolver;
+use crate::solver::sat::SatSolver;
+use crate::solver::{Limit, Solver, SolverError, SolverResult};
+
+pub trait SolverBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn find_solutions_with_limits(
+        &self,
+        grid: Grid,
+        limit: usize,
+        limits: &SearchLimits,
+    ) -> Result<Vec<Grid>, SolverError>;
+
+    fn find_solutions(&self, grid: Grid, limit: usize) -> Result<Vec<Grid>, SolverError> {
+        self.find_solutions_with_limits(grid, limit, &SearchLimits::default())
+    }
+
+    fn count_solutions(&self, grid: Grid, limit: usize) -> Result<usize, SolverError> {
+        let solutions = self.find_solutions(grid, limit)?;
+        Ok(solutions.len())
+    }
+
+    fn solve_with_limits(&self, grid: Grid, limits: &SearchLimits) -> SolverResult {
+        let mut solutions = self.find_solutions_with_limits(grid, 1, limits)?;
+        solutions.pop().ok_or(SolverError::NoSolution)
+    }
+
+    fn solve(&self, grid: Grid) -> SolverResult {
+        self.solve_with_limits(grid, &SearchLimits::default())
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub max_duration: Option<Duration>,
+    pub max_nodes: Option<usize>,
+    pub cancellation_token: Option<CancellationToken>,
+}
+
+pub(crate) struct LimitTracker<'a> {
+    limits: &'a SearchLimits,
+    deadline: Option<Instant>,
+    nodes: AtomicUsize,
+}
+
+impl<'a> LimitTracker<'a> {
+    pub(crate) fn new(limits: &'a SearchLimits) -> Self {
+        let deadline = limits
+            .max_duration
+            .map(|duration| Instant::now() + duration);
+        Self {
+            limits,
+            deadline,
+            nodes: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn visit_node(&self) -> Result<(), SolverError> {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max_nodes) = self.limits.max_nodes {
+            if nodes > max_nodes {
+                return Err(SolverError::LimitExceeded(Limit::Nodes));
+            }
+        }
+        if let Some(cancellation_token) = &self.limits.cancellation_token {
+            if cancellation_token.is_cancelled() {
+                return Err(SolverError::Cancelled);
+            }
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(SolverError::LimitExceeded(Limit::Duration));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Engine {
+    Propagation,
+    Backtracking,
+    DancingLinks,
+    Sat,
+}
+
+impl Engine {
+    pub const ALL: [Engine; 4] = [
+        Engine::Propagation,
+        Engine::Backtracking,
+        Engine::DancingLinks,
+        Engine::Sat,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Engine::Propagation => "propagation",
+            Engine::Backtracking => "backtracking",
+            Engine::DancingLinks => "dancing-links",
+            Engine::Sat => "sat",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|engine| engine.name() == name)
+    }
+
+    pub fn create(&self, config: SolverConfig) -> Box<dyn SolverBackend> {
+        match self {
+            Engine::Propagation => Box::new(PropagationSolver::new(config)),
+            Engine::Backtracking => Box::new(BacktrackingSolver::with_threads(config.threads)),
+            Engine::DancingLinks => Box::new(DancingLinksSolver::new()),
+            Engine::Sat => Box::new(SatSolver::new()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PropagationSolver {
+    config: SolverConfig,
+}
+
+impl PropagationSolver {
+    pub fn new(config: SolverConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl SolverBackend for PropagationSolver {
+    fn name(&self) -> &'static str {
+        Engine::Propagation.name()
+    }
+
+    fn find_solutions_with_limits(
+        &self,
+        grid: Grid,
+        limit: usize,
+        limits: &SearchLimits,
+    ) -> Result<Vec<Grid>, SolverError> {
+        let mut config = self.config.clone();
+        config.max_duration = min_limit(config.max_duration, limits.max_duration);
+        config.max_nodes = min_limit(config.max_nodes, limits.max_nodes);
+        let mut solver = Solver::new(grid, config);
+        if let Some(cancellation_token) = &limits.cancellation_token {
+            solver.set_cancellation_token(cancellation_token.clone());
+        }
+        solver.find_solutions(limit)
+    }
+}
+
+fn min_limit<T: Ord>(first: Option<T>, second: Option<T>) -> Option<T> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.min(second)),
+        (first, second) => first.or(second),
+    }
+}
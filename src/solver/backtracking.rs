@@ -0,0 +1,227 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::sync::Arc;
+
+use crate::grid::cell::Cell;
+use crate::grid::constraints::Constraints;
+use crate::grid::digit::Digit;
+use crate::grid::units::Units;
+use crate::grid::{Grid, GRID_CELLS, SUBGRID_LENGTH};
+use crate::solver::backend::{Engine, LimitTracker, SearchLimits, SolverBackend};
+use crate::solver::parallel::search_parallel;
+use crate::solver::SolverError;
+
+const ALL_VALUES: u16 = ((1 << SUBGRID_LENGTH) - 1) << 1;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BacktrackingSolver {
+    threads: usize,
+}
+
+impl Default for BacktrackingSolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BacktrackingSolver {
+    pub fn new() -> Self {
+        Self::with_threads(1)
+    }
+
+    pub fn with_threads(threads: usize) -> Self {
+        let threads = threads.max(1);
+        Self { threads }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+}
+
+impl SolverBackend for BacktrackingSolver {
+    fn name(&self) -> &'static str {
+        Engine::Backtracking.name()
+    }
+
+    fn find_solutions_with_limits(
+        &self,
+        grid: Grid,
+        limit: usize,
+        limits: &SearchLimits,
+    ) -> Result<Vec<Grid>, SolverError> {
+        let mut solutions = Vec::new();
+        let mut board = match Board::from_grid(&grid) {
+            Some(board) if limit > 0 => board,
+            _ => return Ok(solutions),
+        };
+        if self.threads > 1 {
+            return search_parallel(board, self.threads, limit, limits);
+        }
+        let mut tracker = LimitTracker::new(limits);
+        board.search(&mut tracker, &mut solutions, limit)?;
+        Ok(solutions)
+    }
+}
+
+// Values are kept as bit sets, where bit `n` is set when value `n` is used in the unit. The units
+// are shared between the boards of the parallel search.
+#[derive(Clone)]
+pub(crate) struct Board {
+    values: [u32; GRID_CELLS],
+    used_values: Vec<u16>,
+    units: Arc<Units>,
+    constraints: Constraints,
+}
+
+impl Board {
+    fn from_grid(grid: &Grid) -> Option<Self> {
+        let units = grid.get_units();
+        let mut board = Self {
+            values: [0; GRID_CELLS],
+            used_values: vec![0; units.len()],
+            units: Arc::new(units),
+            constraints: grid.constraints.clone(),
+        };
+        for (cell, digit) in grid.digits.iter().enumerate() {
+            if let Digit::Known(value) = digit {
+                if board.get_candidates(cell) & (1 << value) == 0 {
+                    return None;
+                }
+                board.place(cell, *value);
+            }
+        }
+        Some(board)
+    }
+
+    fn search(
+        &mut self,
+        tracker: &mut LimitTracker,
+        solutions: &mut Vec<Grid>,
+        limit: usize,
+    ) -> Result<(), SolverError> {
+        tracker.visit_node()?;
+        let cell = match self.find_most_constrained_cell() {
+            Some(cell) => cell,
+            None => {
+                solutions.push(self.to_grid());
+                return Ok(());
+            }
+        };
+        let candidates = self.get_candidates(cell);
+        for value in 1..=SUBGRID_LENGTH as u32 {
+            if candidates & (1 << value) == 0 {
+                continue;
+            }
+            self.place(cell, value);
+            let result = self.search(tracker, solutions, limit);
+            self.remove(cell, value);
+            result?;
+            if solutions.len() >= limit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn find_most_constrained_cell(&self) -> Option<usize> {
+        (0..GRID_CELLS)
+            .filter(|cell| self.values[*cell] == 0)
+            .min_by_key(|cell| self.get_candidates(*cell).count_ones())
+    }
+
+    pub(crate) fn get_candidates(&self, cell: usize) -> u16 {
+        let used = self
+            .get_units(cell)
+            .iter()
+            .fold(0, |used, unit| used | self.used_values[*unit]);
+        let candidates = !used & ALL_VALUES;
+        if !self.constraints.has_cell_constraints() {
+            return candidates;
+        }
+        (1..=SUBGRID_LENGTH as u32)
+            .filter(|value| candidates & (1 << value) != 0)
+            .filter(|value| self.constraints.allows(&self.values, cell, *value))
+            .fold(0, |allowed, value| allowed | (1 << value))
+    }
+
+    pub(crate) fn place(&mut self, cell: usize, value: u32) {
+        self.values[cell] = value;
+        for unit in self.units.get_units_of(Cell::from_index(cell)) {
+            self.used_values[*unit] |= 1 << value;
+        }
+    }
+
+    pub(crate) fn remove(&mut self, cell: usize, value: u32) {
+        self.values[cell] = 0;
+        for unit in self.units.get_units_of(Cell::from_index(cell)) {
+            self.used_values[*unit] &= !(1 << value);
+        }
+    }
+
+    fn get_units(&self, cell: usize) -> &[usize] {
+        self.units.get_units_of(Cell::from_index(cell))
+    }
+
+    pub(crate) fn to_grid(&self) -> Grid {
+        let digits = self
+            .values
+            .iter()
+            .map(|value| Digit::Known(*value))
+            .collect();
+        Grid::from_cells(digits, self.constraints.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::parser::Parser;
+    use crate::grid::Subgrid;
+
+    use super::*;
+
+    #[test]
+    fn test_hard_puzzle_has_one_solution() -> Result<(), SolverError> {
+        let grid = Parser::new()
+            .parse(include_str!("../../tests/grids/hard.grid").to_owned())
+            .unwrap();
+        let solver = BacktrackingSolver::new();
+        assert_eq!(1, solver.count_solutions(grid.clone(), 2)?);
+        assert!(solver.solve(grid)?.is_solved_correctly());
+        Ok(())
+    }
+
+    #[test]
+    fn test_conflicting_givens_have_no_solution() {
+        let mut subgrids = vec![Subgrid::default(); 9];
+        subgrids[0] = Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        subgrids[1] = Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let grid = Grid::new(subgrids);
+        let solver = BacktrackingSolver::new();
+        assert_eq!(Err(SolverError::NoSolution), solver.solve(grid.clone()));
+        let solver = BacktrackingSolver::with_threads(4);
+        assert_eq!(Err(SolverError::NoSolution), solver.solve(grid));
+    }
+}
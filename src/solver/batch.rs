@@ -0,0 +1,163 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::num::NonZeroUsize;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::grid::Grid;
+use crate::solver::backend::{SearchLimits, SolverBackend};
+use crate::solver::SolverResult;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchResult {
+    pub index: usize,
+    pub result: SolverResult,
+    pub elapsed: Duration,
+}
+
+impl BatchResult {
+    pub fn new(index: usize, result: SolverResult, elapsed: Duration) -> Self {
+        Self {
+            index,
+            result,
+            elapsed,
+        }
+    }
+}
+
+pub struct BatchSolver {
+    backend: Box<dyn SolverBackend>,
+    limits: SearchLimits,
+    threads: usize,
+}
+
+impl BatchSolver {
+    pub fn new(backend: Box<dyn SolverBackend>) -> Self {
+        let threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        Self {
+            backend,
+            limits: SearchLimits::default(),
+            threads,
+        }
+    }
+
+    pub fn set_limits(&mut self, limits: SearchLimits) {
+        self.limits = limits;
+    }
+
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn solve<I>(&self, grids: I) -> Vec<BatchResult>
+    where
+        I: IntoIterator<Item = Grid>,
+        I::IntoIter: Send,
+    {
+        let grids = Mutex::new(grids.into_iter().enumerate());
+        let (sender, receiver) = mpsc::channel();
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                let sender = sender.clone();
+                let grids = &grids;
+                scope.spawn(move || loop {
+                    let next = grids.lock().unwrap().next();
+                    let (index, grid) = match next {
+                        Some(next) => next,
+                        None => break,
+                    };
+                    let start = Instant::now();
+                    let result = self.backend.solve_with_limits(grid, &self.limits);
+                    let batch_result = BatchResult::new(index, result, start.elapsed());
+                    if sender.send(batch_result).is_err() {
+                        break;
+                    }
+                });
+            }
+        });
+        drop(sender);
+        let mut results: Vec<BatchResult> = receiver.into_iter().collect();
+        results.sort_by_key(|result| result.index);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::parser::Parser;
+    use crate::grid::Subgrid;
+    use crate::solver::backend::Engine;
+    use crate::solver::config::SolverConfig;
+    use crate::solver::SolverError;
+
+    use super::*;
+
+    fn parse(syntax: &str) -> Grid {
+        Parser::new().parse(syntax.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn test_results_are_returned_in_input_order() {
+        let mut conflicting = vec![Subgrid::default(); 9];
+        conflicting[0] = Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        conflicting[1] = Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]);
+        let grids = vec![
+            parse(include_str!("../../tests/grids/extreme.grid")),
+            parse(include_str!("../../tests/grids/easy.grid")),
+            Grid::new(conflicting),
+            parse(include_str!("../../tests/grids/hard.grid")),
+        ];
+        let backend = Engine::DancingLinks.create(SolverConfig::default());
+        let expected: Vec<SolverResult> = grids
+            .iter()
+            .map(|grid| backend.solve(grid.clone()))
+            .collect();
+        assert_eq!(Err(SolverError::NoSolution), expected[2]);
+
+        for threads in [1, 3] {
+            let mut solver = BatchSolver::new(Engine::DancingLinks.create(SolverConfig::default()));
+            solver.set_threads(threads);
+            let results = solver.solve(grids.clone());
+            let indices: Vec<usize> = results.iter().map(|result| result.index).collect();
+            assert_eq!(vec![0, 1, 2, 3], indices);
+            let actual: Vec<SolverResult> =
+                results.into_iter().map(|result| result.result).collect();
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn test_empty_batch_has_no_results() {
+        let solver = BatchSolver::new(Engine::Backtracking.create(SolverConfig::default()));
+        assert!(solver.threads() >= 1);
+        assert!(solver.solve(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Strategy {
+    NakedSingles,
+    HiddenSingles,
+    LockedCandidates,
+    Subsets,
+    CageCombinations,
+    AdjacentPairs,
+    CellConstraints,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 7] = [
+        Strategy::NakedSingles,
+        Strategy::HiddenSingles,
+        Strategy::LockedCandidates,
+        Strategy::Subsets,
+        Strategy::CageCombinations,
+        Strategy::AdjacentPairs,
+        Strategy::CellConstraints,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Strategy::NakedSingles => "naked singles",
+            Strategy::HiddenSingles => "hidden singles",
+            Strategy::LockedCandidates => "locked candidates",
+            Strategy::Subsets => "naked and hidden subsets",
+            Strategy::CageCombinations => "cage combinations",
+            Strategy::AdjacentPairs => "adjacent pairs",
+            Strategy::CellConstraints => "cell constraints",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolverConfig {
+    pub max_duration: Option<Duration>,
+    pub max_passes: Option<usize>,
+    pub max_nodes: Option<usize>,
+    pub max_guesses: Option<usize>,
+    pub strategies: Vec<Strategy>,
+    pub allow_guessing: bool,
+    pub threads: usize,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            max_duration: None,
+            max_passes: None,
+            max_nodes: None,
+            max_guesses: None,
+            strategies: Strategy::ALL.to_vec(),
+            allow_guessing: true,
+            threads: 1,
+        }
+    }
+}
+
+impl SolverConfig {
+    pub fn deduction_only() -> Self {
+        Self {
+            allow_guessing: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_enabled(&self, strategy: Strategy) -> bool {
+        self.strategies.contains(&strategy)
+    }
+}
@@ -0,0 +1,383 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::cell::Cell;
+use crate::grid::constraints::Constraints;
+use crate::grid::digit::Digit;
+use crate::grid::{Grid, GRID_CELLS, SUBGRID_LENGTH};
+use crate::solver::backend::{Engine, LimitTracker, SearchLimits, SolverBackend};
+use crate::solver::SolverError;
+
+const ROOT: usize = 0;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DancingLinksSolver;
+
+impl DancingLinksSolver {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    fn search(
+        grid: &Grid,
+        limits: &SearchLimits,
+        on_solution: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> Result<(), SolverError> {
+        let mut matrix = Matrix::from_grid(grid);
+        let mut tracker = LimitTracker::new(limits);
+        let mut selected_rows = Vec::with_capacity(GRID_CELLS);
+        matrix.search(&mut selected_rows, &mut tracker, on_solution)?;
+        Ok(())
+    }
+
+    fn to_grid(rows: &[usize], constraints: &Constraints) -> Grid {
+        let mut grid = Grid {
+            constraints: constraints.clone(),
+            ..Grid::default()
+        };
+        for row in rows {
+            let (cell, value) = decode_row(*row);
+            grid.set_cell(cell, Digit::Known(value));
+        }
+        grid
+    }
+}
+
+impl SolverBackend for DancingLinksSolver {
+    fn name(&self) -> &'static str {
+        Engine::DancingLinks.name()
+    }
+
+    fn find_solutions_with_limits(
+        &self,
+        grid: Grid,
+        limit: usize,
+        limits: &SearchLimits,
+    ) -> Result<Vec<Grid>, SolverError> {
+        let mut solutions = Vec::new();
+        if limit > 0 {
+            Self::search(&grid, limits, &mut |rows| {
+                solutions.push(Self::to_grid(rows, &grid.constraints));
+                solutions.len() >= limit
+            })?;
+        }
+        Ok(solutions)
+    }
+
+    fn count_solutions(&self, grid: Grid, limit: usize) -> Result<usize, SolverError> {
+        let mut count = 0;
+        if limit > 0 {
+            Self::search(&grid, &SearchLimits::default(), &mut |_| {
+                count += 1;
+                count >= limit
+            })?;
+        }
+        Ok(count)
+    }
+}
+
+// Rows of the exact cover matrix are the candidates "value at (x, y)" and columns are the
+// constraints every solution has to satisfy exactly once: each cell holds a value, and each unit of
+// the grid holds each value. Incomplete units, like cages, hold each value at most once, so their
+// columns are secondary: they are covered, but never chosen.
+struct Matrix {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    column: Vec<usize>,
+    row: Vec<usize>,
+    size: Vec<usize>,
+    constraints: Constraints,
+    values: Vec<u32>,
+}
+
+impl Matrix {
+    fn from_grid(grid: &Grid) -> Self {
+        let units = grid.get_units();
+        let mut matrix = Self::with_headers(GRID_CELLS + units.len() * SUBGRID_LENGTH);
+        for (index, unit) in units.iter().enumerate() {
+            if !unit.is_complete() {
+                for value_index in 0..SUBGRID_LENGTH {
+                    matrix.make_secondary(GRID_CELLS + index * SUBGRID_LENGTH + value_index + 1);
+                }
+            }
+        }
+        matrix.constraints = grid.constraints.clone();
+        matrix.values = grid.get_values();
+        for cell in Cell::all() {
+            let units = units.get_units_of(cell);
+            match grid.get_cell(cell) {
+                Digit::Known(value) => matrix.add_row(encode_row(cell, *value), units),
+                Digit::Unknown(_) => {
+                    for value in 1..=SUBGRID_LENGTH as u32 {
+                        matrix.add_row(encode_row(cell, value), units);
+                    }
+                }
+            }
+        }
+        matrix
+    }
+
+    fn with_headers(columns: usize) -> Self {
+        let headers = columns + 1;
+        Self {
+            left: (0..headers)
+                .map(|node| (node + headers - 1) % headers)
+                .collect(),
+            right: (0..headers).map(|node| (node + 1) % headers).collect(),
+            up: (0..headers).collect(),
+            down: (0..headers).collect(),
+            column: (0..headers).collect(),
+            row: vec![usize::MAX; headers],
+            size: vec![0; headers],
+            constraints: Constraints::new(),
+            values: vec![0; GRID_CELLS],
+        }
+    }
+
+    fn make_secondary(&mut self, header: usize) {
+        self.right[self.left[header]] = self.right[header];
+        self.left[self.right[header]] = self.left[header];
+        self.left[header] = header;
+        self.right[header] = header;
+    }
+
+    fn add_row(&mut self, row: usize, units: &[usize]) {
+        let (cell, value) = decode_row(row);
+        let value_index = value as usize - 1;
+        let mut columns = vec![cell.index()];
+        for unit in units {
+            columns.push(GRID_CELLS + unit * SUBGRID_LENGTH + value_index);
+        }
+        let first = self.left.len();
+        let last_index = columns.len() - 1;
+        for (index, column) in columns.iter().enumerate() {
+            let header = column + 1;
+            let node = first + index;
+            self.left.push(if index == 0 {
+                first + last_index
+            } else {
+                node - 1
+            });
+            self.right
+                .push(if index == last_index { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            self.column.push(header);
+            self.row.push(row);
+            let last = self.up[header];
+            self.down[last] = node;
+            self.up[header] = node;
+            self.size[header] += 1;
+        }
+    }
+
+    fn search(
+        &mut self,
+        selected_rows: &mut Vec<usize>,
+        tracker: &mut LimitTracker,
+        on_solution: &mut dyn FnMut(&[usize]) -> bool,
+    ) -> Result<bool, SolverError> {
+        tracker.visit_node()?;
+        if self.right[ROOT] == ROOT {
+            return Ok(on_solution(selected_rows));
+        }
+        let check_constraints = self.constraints.has_cell_constraints();
+        let (column, size) = self.choose_column(check_constraints);
+        if size == 0 {
+            return Ok(false);
+        }
+        self.cover(column);
+        let mut node = self.down[column];
+        let mut stop = false;
+        while node != column && !stop {
+            if check_constraints && !self.is_allowed(node) {
+                node = self.down[node];
+                continue;
+            }
+            let (cell, value) = decode_row(self.row[node]);
+            let cell = cell.index();
+            let previous_value = self.values[cell];
+            self.values[cell] = value;
+            selected_rows.push(self.row[node]);
+            let mut other = self.right[node];
+            while other != node {
+                self.cover(self.column[other]);
+                other = self.right[other];
+            }
+            stop = self.search(selected_rows, tracker, on_solution)?;
+            let mut other = self.left[node];
+            while other != node {
+                self.uncover(self.column[other]);
+                other = self.left[other];
+            }
+            selected_rows.pop();
+            self.values[cell] = previous_value;
+            node = self.down[node];
+        }
+        self.uncover(column);
+        Ok(stop)
+    }
+
+    // With cell constraints, like cage sums, rows breaking them are not counted, so that dead ends
+    // are found before choosing a row.
+    fn choose_column(&self, check_constraints: bool) -> (usize, usize) {
+        let mut best = (self.right[ROOT], usize::MAX);
+        let mut column = self.right[ROOT];
+        while column != ROOT && best.1 > 0 {
+            let size = if check_constraints {
+                self.count_allowed_rows(column)
+            } else {
+                self.size[column]
+            };
+            if size < best.1 {
+                best = (column, size);
+            }
+            column = self.right[column];
+        }
+        best
+    }
+
+    fn count_allowed_rows(&self, column: usize) -> usize {
+        let mut count = 0;
+        let mut node = self.down[column];
+        while node != column {
+            if self.is_allowed(node) {
+                count += 1;
+            }
+            node = self.down[node];
+        }
+        count
+    }
+
+    fn is_allowed(&self, node: usize) -> bool {
+        let (cell, value) = decode_row(self.row[node]);
+        self.constraints.allows(&self.values, cell.index(), value)
+    }
+
+    fn cover(&mut self, column: usize) {
+        self.right[self.left[column]] = self.right[column];
+        self.left[self.right[column]] = self.left[column];
+        let mut row = self.down[column];
+        while row != column {
+            let mut node = self.right[row];
+            while node != row {
+                self.down[self.up[node]] = self.down[node];
+                self.up[self.down[node]] = self.up[node];
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row = self.down[row];
+        }
+    }
+
+    fn uncover(&mut self, column: usize) {
+        let mut row = self.up[column];
+        while row != column {
+            let mut node = self.left[row];
+            while node != row {
+                self.size[self.column[node]] += 1;
+                self.down[self.up[node]] = node;
+                self.up[self.down[node]] = node;
+                node = self.left[node];
+            }
+            row = self.up[row];
+        }
+        self.right[self.left[column]] = column;
+        self.left[self.right[column]] = column;
+    }
+}
+
+fn encode_row(cell: Cell, value: u32) -> usize {
+    cell.index() * SUBGRID_LENGTH + value as usize - 1
+}
+
+fn decode_row(row: usize) -> (Cell, u32) {
+    let cell = Cell::from_index(row / SUBGRID_LENGTH);
+    let value = (row % SUBGRID_LENGTH) as u32 + 1;
+    (cell, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::parser::Parser;
+    use crate::grid::Subgrid;
+    use crate::solver::config::SolverConfig;
+    use crate::solver::Solver;
+
+    use super::*;
+
+    type TestResult = Result<(), SolverError>;
+
+    fn parse(syntax: &str) -> Grid {
+        Parser::new().parse(syntax.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn test_solution_matches_propagation_solver() -> TestResult {
+        let grid = parse(include_str!("../../tests/grids/extreme.grid"));
+        let expected = Solver::new(grid.clone(), SolverConfig::default()).solve()?;
+        let actual = DancingLinksSolver::new().solve(grid)?;
+        assert_eq!(expected, actual);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solutions_are_enumerated_up_to_limit() -> TestResult {
+        let solver = DancingLinksSolver::new();
+        let solutions = solver.find_solutions(Grid::default(), 5)?;
+        assert_eq!(5, solutions.len());
+        for solution in &solutions {
+            assert!(solution.is_solved() && solution.is_solved_correctly());
+        }
+        assert_eq!(100, solver.count_solutions(Grid::default(), 100)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unique_puzzle_has_one_solution() -> TestResult {
+        let grid = parse(include_str!("../../tests/grids/hard.grid"));
+        assert_eq!(1, DancingLinksSolver::new().count_solutions(grid, 2)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_conflicting_grid_has_no_solution() {
+        let grid = Grid::new(vec![
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::from_digits(vec![1, 0, 0, 0, 0, 0, 0, 0, 0]),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+            Subgrid::default(),
+        ]);
+        let solver = DancingLinksSolver::new();
+        assert_eq!(Ok(0), solver.count_solutions(grid.clone(), 1));
+        assert_eq!(Err(SolverError::NoSolution), solver.solve(grid));
+    }
+}
@@ -23,63 +23,311 @@
  */
 
 use std::borrow::{Borrow, Cow};
-use std::time::{Duration, Instant};
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::Instant;
 
-use crate::grid::digit::{Digit, LocalizedDigit};
-use crate::grid::position::Position;
-use crate::grid::{Grid, GRID_JOINT_SIZE, GRID_SIZE, SUBGRID_LENGTH, SUBGRID_SIZE};
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::{CellConstraint, ALL_CANDIDATES};
+use crate::grid::constraints::get_adjacent_cells;
+use crate::grid::digit::{Digit, LocalizedDigit, UnknownDigit};
+use crate::grid::units::Units;
+use crate::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+use crate::solver::cancellation::CancellationToken;
+use crate::solver::config::{SolverConfig, Strategy};
+use crate::solver::progress::{Progress, ProgressCallback};
+use crate::solver::stats::SolveStats;
+
+pub mod backend;
+pub mod backtracking;
+pub mod batch;
+pub mod cancellation;
+pub mod config;
+pub mod dlx;
+mod parallel;
+pub mod progress;
+pub mod samurai;
+pub mod sat;
+pub mod stats;
 
 pub type SolverResult = Result<Grid, SolverError>;
-pub type SolverError = Cow<'static, str>;
+
+// Larger naked subsets leave hidden subsets of at most this size in the same unit.
+const MAX_SUBSET_SIZE: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Duration,
+    Passes,
+    Nodes,
+    Guesses,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverError {
+    NoSolution,
+    GuessingRequired,
+    LimitExceeded(Limit),
+    Cancelled,
+}
+
+impl Display for SolverError {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            SolverError::NoSolution => "Grid has no solution",
+            SolverError::GuessingRequired => "Grid cannot be solved without guessing",
+            SolverError::LimitExceeded(Limit::Duration) => "Time limit has been exceeded",
+            SolverError::LimitExceeded(Limit::Passes) => "Pass limit has been exceeded",
+            SolverError::LimitExceeded(Limit::Nodes) => "Node limit has been exceeded",
+            SolverError::LimitExceeded(Limit::Guesses) => "Guess limit has been exceeded",
+            SolverError::Cancelled => "Solving has been cancelled",
+        };
+        formatter.write_str(message)
+    }
+}
+
+impl From<SolverError> for Cow<'static, str> {
+    fn from(error: SolverError) -> Self {
+        error.to_string().into()
+    }
+}
 
 pub struct Solver {
     grid: Grid,
+    config: SolverConfig,
+    cancellation_token: Option<CancellationToken>,
+    progress_callback: Option<ProgressCallback>,
+    deadline: Option<Instant>,
+    stats: SolveStats,
+    nodes: usize,
+    depth: usize,
 }
 
 impl Solver {
-    pub fn new(grid: Grid) -> Self {
-        Self { grid }
+    pub fn new(grid: Grid, config: SolverConfig) -> Self {
+        Self {
+            grid,
+            config,
+            cancellation_token: None,
+            progress_callback: None,
+            deadline: None,
+            stats: SolveStats::new(),
+            nodes: 0,
+            depth: 0,
+        }
     }
 
-    pub fn solve(mut self) -> SolverResult {
+    pub fn set_cancellation_token(&mut self, cancellation_token: CancellationToken) {
+        self.cancellation_token = Some(cancellation_token);
+    }
+
+    pub fn set_progress_callback<F>(&mut self, progress_callback: F)
+    where
+        F: FnMut(&Progress) + Send + 'static,
+    {
+        self.progress_callback = Some(Box::new(progress_callback));
+    }
+
+    pub fn solve(self) -> SolverResult {
+        let mut solutions = self.find_solutions(1)?;
+        solutions.pop().ok_or(SolverError::NoSolution)
+    }
+
+    pub fn solve_with_stats(self) -> (SolverResult, SolveStats) {
+        let (result, stats) = self.find_solutions_with_stats(1);
+        let result =
+            result.and_then(|mut solutions| solutions.pop().ok_or(SolverError::NoSolution));
+        (result, stats)
+    }
+
+    pub fn find_solutions(self, limit: usize) -> Result<Vec<Grid>, SolverError> {
+        let (result, _) = self.find_solutions_with_stats(limit);
+        result
+    }
+
+    pub fn find_solutions_with_stats(
+        mut self,
+        limit: usize,
+    ) -> (Result<Vec<Grid>, SolverError>, SolveStats) {
         let start = Instant::now();
-        while !self.grid.is_solved() && start.elapsed() < Duration::from_secs(1) {
-            self.set_possible_values();
+        self.deadline = self.config.max_duration.map(|duration| start + duration);
+        let mut solutions = Vec::new();
+        let result = if limit > 0 {
+            self.search(&mut solutions, limit)
+        } else {
+            Ok(())
+        };
+        self.stats.elapsed = start.elapsed();
+        (result.map(|_| solutions), self.stats)
+    }
+
+    // Applies the strategies without guessing and returns the grid with the deduced digits and
+    // the possible values of the remaining cells, even when it is not solved.
+    pub fn deduce(mut self) -> SolverResult {
+        self.deadline = self
+            .config
+            .max_duration
+            .map(|duration| Instant::now() + duration);
+        self.propagate()?;
+        Ok(self.grid)
+    }
+
+    fn search(&mut self, solutions: &mut Vec<Grid>, limit: usize) -> Result<(), SolverError> {
+        if let Some(max_nodes) = self.config.max_nodes {
+            if self.nodes >= max_nodes {
+                return Err(SolverError::LimitExceeded(Limit::Nodes));
+            }
+        }
+        self.nodes += 1;
+        match self.propagate() {
+            Err(SolverError::NoSolution) => return Ok(()),
+            result => result?,
+        }
+        if self.grid.is_solved() {
+            solutions.push(self.grid.clone());
+            return Ok(());
+        }
+        if !self.config.allow_guessing {
+            return Err(SolverError::GuessingRequired);
+        }
+        let (x, y, values) = self.find_guess_candidates();
+        for value in values {
+            if let Some(max_guesses) = self.config.max_guesses {
+                if self.stats.guesses >= max_guesses {
+                    return Err(SolverError::LimitExceeded(Limit::Guesses));
+                }
+            }
+            self.stats.guesses += 1;
+            let saved_grid = self.grid.clone();
+            let saved_solutions = solutions.len();
+            self.grid.set_digit(x, y, Digit::Known(value));
+            self.depth += 1;
+            self.stats.max_depth = self.stats.max_depth.max(self.depth);
+            let result = self.search(solutions, limit);
+            self.depth -= 1;
+            self.grid = saved_grid;
+            result?;
+            if solutions.len() >= limit {
+                break;
+            }
+            if solutions.len() == saved_solutions {
+                self.stats.backtracks += 1;
+            }
+        }
+        Ok(())
+    }
+
+    fn propagate(&mut self) -> Result<(), SolverError> {
+        while !self.grid.is_solved() {
+            self.check_limits()?;
+            self.stats.passes += 1;
+            let previous_grid = self.grid.clone();
+            self.run_pass();
+            self.report_progress();
+            if self.has_contradiction() {
+                return Err(SolverError::NoSolution);
+            }
+            if self.grid == previous_grid {
+                return Ok(());
+            }
+        }
+        if self.grid.validate().is_valid() {
+            Ok(())
+        } else {
+            Err(SolverError::NoSolution)
+        }
+    }
+
+    fn run_pass(&mut self) {
+        let units = self.grid.get_units();
+        self.set_possible_values(&units);
+        if self.config.is_enabled(Strategy::NakedSingles) {
             self.set_single_possible_values();
-            self.set_possible_values();
-            self.find_single_possible_solutions_for_subgrids();
-            self.set_possible_values();
-            self.find_single_possible_solutions_for_columns();
-            self.set_possible_values();
-            self.find_single_possible_solutions_for_rows();
+            self.set_possible_values(&units);
+        }
+        if self.config.is_enabled(Strategy::HiddenSingles) {
+            self.find_hidden_singles(&units);
+            self.set_possible_values(&units);
         }
-        Ok(self.grid)
     }
 
-    fn find_single_possible_solutions_for_subgrids(&mut self) {
-        for subgrid in &mut self.grid.subgrids {
-            let digits = subgrid.localized_digits();
-            Self::get_solutions(digits)
-                .into_iter()
-                .map(|solution| solution.into_owned_tuple())
-                .for_each(|(digit, x, y)| subgrid.set_digit(x, y, digit));
+    fn check_limits(&self) -> Result<(), SolverError> {
+        if let Some(cancellation_token) = &self.cancellation_token {
+            if cancellation_token.is_cancelled() {
+                return Err(SolverError::Cancelled);
+            }
+        }
+        if let Some(max_passes) = self.config.max_passes {
+            if self.stats.passes >= max_passes {
+                return Err(SolverError::LimitExceeded(Limit::Passes));
+            }
+        }
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(SolverError::LimitExceeded(Limit::Duration));
+            }
         }
+        Ok(())
     }
 
-    fn find_single_possible_solutions_for_columns(&mut self) {
+    fn report_progress(&mut self) {
+        let grid = &self.grid;
+        if let Some(progress_callback) = &mut self.progress_callback {
+            let filled_cells = (0..GRID_JOINT_SIZE)
+                .flat_map(|y| (0..GRID_JOINT_SIZE).map(move |x| (x, y)))
+                .filter(|(x, y)| matches!(grid.get_digit(*x, *y), Digit::Known(_)))
+                .count();
+            let progress = Progress::new(self.nodes, filled_cells, self.depth);
+            progress_callback(&progress);
+        }
+    }
+
+    fn has_contradiction(&self) -> bool {
         for x in 0..GRID_JOINT_SIZE {
-            let column = self.grid.get_vertical_localized_digits(x);
-            Self::get_solutions(column)
-                .into_iter()
-                .map(|solution| solution.into_owned_tuple())
-                .for_each(|(digit, x, y)| self.grid.set_digit(x, y, digit));
+            for y in 0..GRID_JOINT_SIZE {
+                if let Digit::Unknown(digit) = self.grid.get_digit(x, y) {
+                    if digit.possible_values.is_empty() {
+                        return true;
+                    }
+                }
+            }
         }
+        !self.grid.validate().is_valid()
     }
 
-    fn find_single_possible_solutions_for_rows(&mut self) {
+    fn find_guess_candidates(&self) -> (usize, usize, Vec<u32>) {
+        let mut best: Option<(usize, usize, &Vec<u32>)> = None;
         for y in 0..GRID_JOINT_SIZE {
-            let row = self.grid.get_horizontal_localized_digits(y);
-            Self::get_solutions(row)
+            for x in 0..GRID_JOINT_SIZE {
+                if let Digit::Unknown(digit) = self.grid.get_digit(x, y) {
+                    let values = &digit.possible_values;
+                    match best {
+                        Some((_, _, best_values)) if best_values.len() <= values.len() => {}
+                        _ => best = Some((x, y, values)),
+                    }
+                }
+            }
+        }
+        // The search only guesses in unsolved grids, so there is always an unknown digit.
+        let (x, y, values) = best.unwrap();
+        (x, y, values.clone())
+    }
+
+    fn find_hidden_singles(&mut self, units: &Units) {
+        // Values may be missing from incomplete units, like cages, so hidden singles do not apply.
+        for unit in units.iter().filter(|unit| unit.is_complete()) {
+            let digits = unit
+                .cells
+                .iter()
+                .map(|cell| {
+                    let digit = self.grid.get_digit(cell.column(), cell.row());
+                    LocalizedDigit::from_borrowed(digit, cell.column(), cell.row())
+                })
+                .collect();
+            let solutions = Self::get_solutions(digits);
+            self.stats
+                .add_placements(Strategy::HiddenSingles, solutions.len());
+            solutions
                 .into_iter()
                 .map(|solution| solution.into_owned_tuple())
                 .for_each(|(digit, x, y)| self.grid.set_digit(x, y, digit));
@@ -121,6 +369,7 @@ impl Solver {
     }
 
     fn set_single_possible_values(&mut self) {
+        let mut placements = 0;
         for x in 0..GRID_JOINT_SIZE {
             for y in 0..GRID_JOINT_SIZE {
                 let digit = self.grid.get_digit_mut(x, y);
@@ -130,133 +379,373 @@ impl Solver {
                         let value = values.first().unwrap();
                         let known_digit = Digit::Known(*value);
                         *digit = known_digit;
+                        placements += 1;
                     }
                 }
             }
         }
+        self.stats
+            .add_placements(Strategy::NakedSingles, placements);
     }
 
-    fn set_possible_values(&mut self) {
+    fn set_possible_values(&mut self, units: &Units) {
         let grid_clone = self.grid.clone();
+        let used_values = units
+            .iter()
+            .map(|unit| {
+                unit.cells.iter().fold(0u16, |used, cell| {
+                    match grid_clone.get_digit(cell.column(), cell.row()) {
+                        Digit::Known(value) => used | (1 << value),
+                        Digit::Unknown(_) => used,
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
         for x in 0..GRID_JOINT_SIZE {
             for y in 0..GRID_JOINT_SIZE {
-                let digit = self.grid.get_digit_mut(x, y);
-                if let Digit::Unknown(digit) = digit {
-                    let neighbours = grid_clone.get_neighbour_digits(x, y);
-                    let supplement = Self::get_complement(neighbours);
-                    digit.possible_values = supplement;
+                let mut used = units
+                    .get_cell_units(x, y)
+                    .iter()
+                    .fold(0u16, |used, unit| used | used_values[*unit]);
+                for cell in grid_clone.constraints.get_visible_cells(x, y) {
+                    if let Digit::Known(value) = grid_clone.get_digit(cell.column(), cell.row()) {
+                        used |= 1 << value;
+                    }
+                }
+                if let Digit::Unknown(digit) = self.grid.get_digit_mut(x, y) {
+                    digit.possible_values = (1..=SUBGRID_LENGTH as u32)
+                        .filter(|value| used & (1 << value) == 0)
+                        .collect();
                 }
             }
         }
-        self.eliminate_impossible_possible_values_in_columns();
-        self.eliminate_impossible_possible_values_in_rows();
-    }
-
-    fn get_complement(neighbours: Vec<&Digit>) -> Vec<u32> {
-        let mut complement = (1..=SUBGRID_LENGTH as u32).collect::<Vec<_>>();
-        for neighbour in neighbours {
-            if let Digit::Known(neighbour) = neighbour {
-                complement.retain(|element| element != neighbour);
-            }
+        if self.config.is_enabled(Strategy::LockedCandidates) {
+            let basic_grid = self.grid.clone();
+            self.eliminate_locked_candidates(units);
+            let eliminations = Self::count_new_eliminations(&grid_clone, &basic_grid, &self.grid);
+            self.stats
+                .add_eliminations(Strategy::LockedCandidates, eliminations);
+        }
+        if self.config.is_enabled(Strategy::Subsets) {
+            let basic_grid = self.grid.clone();
+            self.eliminate_impossible_possible_values_in_subsets(units);
+            let eliminations = Self::count_new_eliminations(&grid_clone, &basic_grid, &self.grid);
+            self.stats.add_eliminations(Strategy::Subsets, eliminations);
+        }
+        if self.config.is_enabled(Strategy::CageCombinations)
+            && !self.grid.constraints.cages.is_empty()
+        {
+            let basic_grid = self.grid.clone();
+            self.eliminate_impossible_possible_values_in_cages();
+            let eliminations = Self::count_new_eliminations(&grid_clone, &basic_grid, &self.grid);
+            self.stats
+                .add_eliminations(Strategy::CageCombinations, eliminations);
+        }
+        if self.config.is_enabled(Strategy::AdjacentPairs)
+            && self.grid.constraints.has_pair_constraints()
+        {
+            let basic_grid = self.grid.clone();
+            self.eliminate_impossible_possible_values_in_pairs();
+            let eliminations = Self::count_new_eliminations(&grid_clone, &basic_grid, &self.grid);
+            self.stats
+                .add_eliminations(Strategy::AdjacentPairs, eliminations);
+        }
+        if self.config.is_enabled(Strategy::CellConstraints)
+            && self
+                .grid
+                .constraints
+                .get_cell_constraints()
+                .next()
+                .is_some()
+        {
+            let basic_grid = self.grid.clone();
+            self.eliminate_impossible_possible_values_in_cell_constraints();
+            let eliminations = Self::count_new_eliminations(&grid_clone, &basic_grid, &self.grid);
+            self.stats
+                .add_eliminations(Strategy::CellConstraints, eliminations);
         }
-        complement
     }
 
-    fn eliminate_impossible_possible_values_in_columns(&mut self) {
-        for grid_row in 0..GRID_SIZE {
-            for column in 0..GRID_JOINT_SIZE {
-                let mut column_values = Vec::with_capacity(9);
-                for subgrid_y in SUBGRID_SIZE * grid_row..SUBGRID_SIZE * (grid_row + 1) {
-                    let digit = self.grid.get_digit(column, subgrid_y);
-                    if let Digit::Unknown(digit) = digit {
-                        let mut possible_values = digit.possible_values.clone();
-                        column_values.append(&mut possible_values)
+    // A naked subset is a set of cells of a unit whose values together are just as many as the
+    // cells, so the other cells cannot take them. A hidden subset is a set of values that only as
+    // many cells of a complete unit can take, so these cells cannot take other values.
+    fn eliminate_impossible_possible_values_in_subsets(&mut self, units: &Units) {
+        for unit in units {
+            let cells = unit
+                .cells
+                .iter()
+                .filter_map(
+                    |cell| match self.grid.get_digit(cell.column(), cell.row()) {
+                        Digit::Unknown(digit) => Some((cell, Self::to_candidates(digit))),
+                        Digit::Known(_) => None,
+                    },
+                )
+                .collect::<Vec<_>>();
+            let max_size = MAX_SUBSET_SIZE.min(cells.len().saturating_sub(1)) as u32;
+            let mut allowed = vec![ALL_CANDIDATES; cells.len()];
+            let candidates = cells
+                .iter()
+                .map(|(_, candidates)| u32::from(*candidates))
+                .collect::<Vec<_>>();
+            Self::find_subsets(&candidates, max_size, &mut |subset, values| {
+                for (index, allowed) in allowed.iter_mut().enumerate() {
+                    if subset & (1 << index) == 0 {
+                        *allowed &= !values as u16;
                     }
                 }
-                let subgrid = self.grid.get_subgrid(column, SUBGRID_SIZE * grid_row);
-                let mut positions = Vec::with_capacity(SUBGRID_SIZE);
-                let x = column % SUBGRID_SIZE;
-                for y in 0..SUBGRID_SIZE {
-                    let position = Position::new(x, y);
-                    positions.push(position)
-                }
-                let mut values_to_remove = Vec::with_capacity(SUBGRID_LENGTH);
-                let digits = subgrid.get_digits_excluding(positions);
-                for column_value in column_values {
-                    if !Self::is_in_digits(column_value, &digits) {
-                        values_to_remove.push(column_value);
-                    }
-                }
-                for value in values_to_remove {
-                    for row in 0..SUBGRID_SIZE * grid_row {
-                        let digit = self.grid.get_digit_mut(column, row);
-                        if let Digit::Unknown(digit) = digit {
-                            digit.possible_values.retain(|element| *element != value);
-                        }
-                    }
-                    for row in SUBGRID_SIZE * (grid_row + 1)..GRID_JOINT_SIZE {
-                        let digit = self.grid.get_digit_mut(column, row);
-                        if let Digit::Unknown(digit) = digit {
-                            digit.possible_values.retain(|element| *element != value);
+            });
+            if unit.is_complete() {
+                let holders = (0..=SUBGRID_LENGTH)
+                    .map(|value| {
+                        (0..cells.len())
+                            .filter(|index| candidates[*index] & (1 << value) != 0)
+                            .fold(0, |holders, index| holders | (1 << index))
+                    })
+                    .collect::<Vec<_>>();
+                Self::find_subsets(&holders, max_size, &mut |values, holders| {
+                    for (index, allowed) in allowed.iter_mut().enumerate() {
+                        if holders & (1 << index) != 0 {
+                            *allowed &= values as u16;
                         }
                     }
+                });
+            }
+            for ((cell, candidates), allowed) in cells.iter().zip(allowed) {
+                if candidates & !allowed == 0 {
+                    continue;
+                }
+                if let Digit::Unknown(digit) = self.grid.get_digit_mut(cell.column(), cell.row()) {
+                    digit
+                        .possible_values
+                        .retain(|value| allowed & (1 << value) != 0);
                 }
             }
         }
     }
 
-    fn eliminate_impossible_possible_values_in_rows(&mut self) {
-        for grid_column in 0..GRID_SIZE {
-            for row in 0..GRID_JOINT_SIZE {
-                let mut row_values = Vec::with_capacity(9);
-                for subgrid_x in SUBGRID_SIZE * grid_column..SUBGRID_SIZE * (grid_column + 1) {
-                    let digit = self.grid.get_digit(subgrid_x, row);
-                    if let Digit::Unknown(digit) = digit {
-                        let mut possible_values = digit.possible_values.clone();
-                        row_values.append(&mut possible_values)
-                    }
+    // Finds the sets of two up to `max_size` of the given bit sets whose union has as many
+    // elements as the set, and passes the indices of the set and the union to `found`. Empty bit
+    // sets are never part of a set.
+    fn find_subsets(masks: &[u32], max_size: u32, found: &mut dyn FnMut(u32, u32)) {
+        Self::extend_subsets(masks, 0, 0, 0, max_size, found);
+    }
+
+    fn extend_subsets(
+        masks: &[u32],
+        start: usize,
+        subset: u32,
+        union: u32,
+        max_size: u32,
+        found: &mut dyn FnMut(u32, u32),
+    ) {
+        for index in start..masks.len() {
+            let union = union | masks[index];
+            if masks[index] == 0 || union.count_ones() > max_size {
+                continue;
+            }
+            let subset = subset | (1 << index);
+            let size = subset.count_ones();
+            if size >= 2 && union.count_ones() == size {
+                found(subset, union);
+            }
+            if size < max_size {
+                Self::extend_subsets(masks, index + 1, subset, union, max_size, found);
+            }
+        }
+    }
+
+    fn to_candidates(digit: &UnknownDigit) -> u16 {
+        digit
+            .possible_values
+            .iter()
+            .fold(0u16, |candidates, value| candidates | (1 << value))
+    }
+
+    // Repeats until nothing changes, so that restrictions travel along chains of constraints
+    // like r1c1 < r1c2 < r1c3 in a single pass.
+    fn eliminate_impossible_possible_values_in_cell_constraints(&mut self) {
+        let constraints = self.grid.constraints.clone();
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for constraint in constraints.get_cell_constraints() {
+                changed |= self.restrict_cell_constraint(constraint);
+            }
+        }
+    }
+
+    fn restrict_cell_constraint(&mut self, constraint: &dyn CellConstraint) -> bool {
+        let cells = constraint.get_cells();
+        let mut candidates = cells
+            .iter()
+            .map(
+                |cell| match self.grid.get_digit(cell.column(), cell.row()) {
+                    Digit::Known(value) => 1 << value,
+                    Digit::Unknown(digit) => digit
+                        .possible_values
+                        .iter()
+                        .fold(0u16, |candidates, value| candidates | (1 << value)),
+                },
+            )
+            .collect::<Vec<_>>();
+        constraint.restrict(&mut candidates);
+        let mut changed = false;
+        for (cell, candidates) in cells.iter().zip(candidates) {
+            if let Digit::Unknown(digit) = self.grid.get_digit_mut(cell.column(), cell.row()) {
+                let count = digit.possible_values.len();
+                digit
+                    .possible_values
+                    .retain(|value| candidates & (1 << value) != 0);
+                changed |= digit.possible_values.len() != count;
+            }
+        }
+        changed
+    }
+
+    // Keeps only the values that some value of each adjacent cell can accompany, like the
+    // consecutive values next to a white dot.
+    fn eliminate_impossible_possible_values_in_pairs(&mut self) {
+        let grid = self.grid.clone();
+        let constraints = &grid.constraints;
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                let digit = match self.grid.get_digit_mut(x, y) {
+                    Digit::Unknown(digit) => digit,
+                    Digit::Known(_) => continue,
+                };
+                let cell = Cell::new(x, y);
+                for other in get_adjacent_cells(x, y) {
+                    let other_values = match grid.get_digit(other.column(), other.row()) {
+                        Digit::Known(value) => vec![*value],
+                        Digit::Unknown(other_digit) => other_digit.possible_values.clone(),
+                    };
+                    digit.possible_values.retain(|value| {
+                        other_values.iter().any(|other_value| {
+                            other_value != value
+                                && constraints.allows_pair(&cell, &other, *value, *other_value)
+                        })
+                    });
                 }
-                let subgrid = self.grid.get_subgrid(SUBGRID_SIZE * grid_column, row);
-                let mut positions = Vec::with_capacity(SUBGRID_SIZE);
-                let y = row % SUBGRID_SIZE;
-                for x in 0..SUBGRID_SIZE {
-                    let position = Position::new(x, y);
-                    positions.push(position)
+            }
+        }
+    }
+
+    // Keeps only the values that are part of some combination matching the sum and the values
+    // already placed in the cage, where every remaining cell can still take one of its values.
+    fn eliminate_impossible_possible_values_in_cages(&mut self) {
+        let cages = self.grid.constraints.cages.clone();
+        for cage in cages {
+            let mut known = 0u16;
+            let mut unknown = Vec::with_capacity(cage.cells.len());
+            for cell in &cage.cells {
+                match self.grid.get_digit(cell.column(), cell.row()) {
+                    Digit::Known(value) => known |= 1 << value,
+                    Digit::Unknown(digit) => unknown.push(
+                        digit
+                            .possible_values
+                            .iter()
+                            .fold(0u16, |candidates, value| candidates | (1 << value)),
+                    ),
                 }
-                let mut values_to_remove = Vec::with_capacity(SUBGRID_LENGTH);
-                let digits = subgrid.get_digits_excluding(positions);
-                for row_value in row_values {
-                    if !Self::is_in_digits(row_value, &digits) {
-                        values_to_remove.push(row_value);
-                    }
+            }
+            let all_candidates = unknown.iter().fold(0, |all, candidates| all | candidates);
+            let allowed = cage
+                .combinations()
+                .into_iter()
+                .filter(|combination| combination & known == known)
+                .map(|combination| combination & !known)
+                .filter(|remaining| remaining & !all_candidates == 0)
+                .filter(|remaining| unknown.iter().all(|candidates| remaining & candidates != 0))
+                .fold(0, |allowed, remaining| allowed | remaining);
+            for cell in &cage.cells {
+                if let Digit::Unknown(digit) = self.grid.get_digit_mut(cell.column(), cell.row()) {
+                    digit
+                        .possible_values
+                        .retain(|value| allowed & (1 << value) != 0);
                 }
-                for value in values_to_remove {
-                    for column in 0..SUBGRID_SIZE * grid_column {
-                        let digit = self.grid.get_digit_mut(column, row);
-                        if let Digit::Unknown(digit) = digit {
-                            digit.possible_values.retain(|element| *element != value);
-                        }
-                    }
-                    for column in SUBGRID_SIZE * (grid_column + 1)..GRID_JOINT_SIZE {
-                        let digit = self.grid.get_digit_mut(column, row);
-                        if let Digit::Unknown(digit) = digit {
-                            digit.possible_values.retain(|element| *element != value);
-                        }
-                    }
+            }
+        }
+    }
+
+    // Candidates are recomputed from scratch on every call, so only values that were still possible
+    // before the call are counted, to not count the same elimination on every pass.
+    fn count_new_eliminations(previous: &Grid, before: &Grid, after: &Grid) -> usize {
+        let mut eliminations = 0;
+        for x in 0..GRID_JOINT_SIZE {
+            for y in 0..GRID_JOINT_SIZE {
+                let digits = (
+                    previous.get_digit(x, y),
+                    before.get_digit(x, y),
+                    after.get_digit(x, y),
+                );
+                if let (Digit::Unknown(previous), Digit::Unknown(before), Digit::Unknown(after)) =
+                    digits
+                {
+                    eliminations += before
+                        .possible_values
+                        .iter()
+                        .filter(|value| !after.possible_values.contains(value))
+                        .filter(|value| {
+                            previous.possible_values.is_empty()
+                                || previous.possible_values.contains(value)
+                        })
+                        .count();
                 }
             }
         }
+        eliminations
     }
 
-    fn is_in_digits(value: u32, digits: &[&Digit]) -> bool {
-        for digit in digits {
-            if let Digit::Unknown(digit) = digit {
-                if digit.possible_values.contains(&value) {
-                    return true;
+    // A value that the cells of a complete unit can only take where the unit meets other units,
+    // like a row of a subgrid, cannot be taken by the other cells of these units.
+    fn eliminate_locked_candidates(&mut self, units: &Units) {
+        for (index, unit) in units.iter().enumerate() {
+            if !unit.is_complete() {
+                continue;
+            }
+            let mut placed = 0;
+            let cells = unit
+                .cells
+                .iter()
+                .filter_map(
+                    |cell| match self.grid.get_digit(cell.column(), cell.row()) {
+                        Digit::Known(value) => {
+                            placed |= 1 << value;
+                            None
+                        }
+                        Digit::Unknown(digit) => Some((cell, Self::to_candidates(digit))),
+                    },
+                )
+                .collect::<Vec<_>>();
+            let mut locked = Vec::with_capacity(SUBGRID_LENGTH);
+            for value in 1..=SUBGRID_LENGTH as u32 {
+                if placed & (1 << value) != 0 {
+                    continue;
+                }
+                locked.clear();
+                locked.extend(
+                    cells
+                        .iter()
+                        .filter(|(_, candidates)| candidates & (1 << value) != 0)
+                        .map(|(cell, _)| *cell),
+                );
+                let others = units
+                    .get_common_units(&locked)
+                    .filter(|other| *other != index);
+                for other in others {
+                    for cell in &units.units[other].cells {
+                        if unit.contains(cell.column(), cell.row()) {
+                            continue;
+                        }
+                        if let Digit::Unknown(digit) =
+                            self.grid.get_digit_mut(cell.column(), cell.row())
+                        {
+                            digit.possible_values.retain(|element| *element != value);
+                        }
+                    }
                 }
             }
         }
-        false
     }
 }
 
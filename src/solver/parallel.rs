@@ -0,0 +1,264 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::thread;
+
+use crate::grid::{Grid, SUBGRID_LENGTH};
+use crate::solver::backend::{LimitTracker, SearchLimits};
+use crate::solver::backtracking::Board;
+use crate::solver::SolverError;
+
+// Every worker owns a queue of subtrees. Workers take the deepest subtree from their own queue and
+// steal the shallowest one from the others. While any worker is idle, the busy ones give away the
+// remaining branches of the node they are visiting instead of exploring them themselves.
+struct Queues {
+    queues: Vec<VecDeque<Board>>,
+    // Subtrees that are queued or being searched.
+    pending: usize,
+}
+
+impl Queues {
+    fn take(&mut self, id: usize) -> Option<Board> {
+        if let Some(board) = self.queues[id].pop_back() {
+            return Some(board);
+        }
+        let count = self.queues.len();
+        (1..count)
+            .map(|offset| (id + offset) % count)
+            .find_map(|victim| self.queues[victim].pop_front())
+    }
+}
+
+struct Shared<'a> {
+    queues: Mutex<Queues>,
+    // Idle workers wait here until a subtree is shared or the search is over.
+    changed: Condvar,
+    idle: AtomicUsize,
+    stop: AtomicBool,
+    solutions: Mutex<Vec<Grid>>,
+    error: Mutex<Option<SolverError>>,
+    tracker: LimitTracker<'a>,
+    limit: usize,
+}
+
+impl Shared<'_> {
+    fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+
+    fn halt(&self) {
+        self.stop.store(true, Ordering::SeqCst);
+        // Taking the lock makes sure that no worker is between checking the flag and waiting.
+        let _queues = self.queues.lock().unwrap();
+        self.changed.notify_all();
+    }
+
+    fn add_solution(&self, solution: Grid) {
+        let mut solutions = self.solutions.lock().unwrap();
+        if solutions.len() < self.limit {
+            solutions.push(solution);
+        }
+        if solutions.len() >= self.limit {
+            self.halt();
+        }
+    }
+
+    fn fail(&self, error: SolverError) {
+        self.error.lock().unwrap().get_or_insert(error);
+        self.halt();
+    }
+}
+
+struct Worker<'a, 'b> {
+    id: usize,
+    shared: &'a Shared<'b>,
+}
+
+impl Worker<'_, '_> {
+    fn run(&self) {
+        while let Some(mut board) = self.next_task() {
+            if let Err(error) = self.search(&mut board) {
+                self.shared.fail(error);
+            }
+            let mut queues = self.shared.queues.lock().unwrap();
+            queues.pending -= 1;
+            if queues.pending == 0 {
+                self.shared.changed.notify_all();
+            }
+        }
+    }
+
+    fn next_task(&self) -> Option<Board> {
+        let mut queues = self.shared.queues.lock().unwrap();
+        loop {
+            if self.shared.is_stopped() || queues.pending == 0 {
+                return None;
+            }
+            if let Some(board) = queues.take(self.id) {
+                return Some(board);
+            }
+            self.shared.idle.fetch_add(1, Ordering::SeqCst);
+            queues = self.shared.changed.wait(queues).unwrap();
+            self.shared.idle.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    fn share(&self, board: Board) {
+        let mut queues = self.shared.queues.lock().unwrap();
+        queues.pending += 1;
+        queues.queues[self.id].push_back(board);
+        self.shared.changed.notify_one();
+    }
+
+    fn search(&self, board: &mut Board) -> Result<(), SolverError> {
+        if self.shared.is_stopped() {
+            return Ok(());
+        }
+        self.shared.tracker.visit_node()?;
+        let cell = match board.find_most_constrained_cell() {
+            Some(cell) => cell,
+            None => {
+                self.shared.add_solution(board.to_grid());
+                return Ok(());
+            }
+        };
+        let candidates = board.get_candidates(cell);
+        let mut values = (1..=SUBGRID_LENGTH as u32)
+            .filter(|value| candidates & (1 << value) != 0)
+            .peekable();
+        while let Some(value) = values.next() {
+            if values.peek().is_some() && self.shared.idle.load(Ordering::SeqCst) > 0 {
+                let mut branch = board.clone();
+                branch.place(cell, value);
+                self.share(branch);
+                continue;
+            }
+            board.place(cell, value);
+            let result = self.search(board);
+            board.remove(cell, value);
+            result?;
+            if self.shared.is_stopped() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn search_parallel(
+    board: Board,
+    threads: usize,
+    limit: usize,
+    limits: &SearchLimits,
+) -> Result<Vec<Grid>, SolverError> {
+    let mut queues = Queues {
+        queues: vec![VecDeque::new(); threads],
+        pending: 1,
+    };
+    queues.queues[0].push_back(board);
+    let shared = Shared {
+        queues: Mutex::new(queues),
+        changed: Condvar::new(),
+        idle: AtomicUsize::new(0),
+        stop: AtomicBool::new(false),
+        solutions: Mutex::new(Vec::new()),
+        error: Mutex::new(None),
+        tracker: LimitTracker::new(limits),
+        limit,
+    };
+    thread::scope(|scope| {
+        for id in 0..threads {
+            let worker = Worker {
+                id,
+                shared: &shared,
+            };
+            scope.spawn(move || worker.run());
+        }
+    });
+    if let Some(error) = shared.error.into_inner().unwrap() {
+        return Err(error);
+    }
+    Ok(shared.solutions.into_inner().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::grid::parser::Parser;
+    use crate::solver::backend::SolverBackend;
+    use crate::solver::backtracking::BacktrackingSolver;
+    use crate::solver::Limit;
+
+    use super::*;
+
+    fn parse(syntax: &str) -> Grid {
+        Parser::new().parse(syntax.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn test_parallel_search_matches_sequential_search() -> Result<(), SolverError> {
+        let grids = [
+            parse(include_str!("../../tests/grids/hard.grid")),
+            parse(include_str!("../../tests/grids/extreme.grid")),
+        ];
+        let sequential = BacktrackingSolver::new();
+        let parallel = BacktrackingSolver::with_threads(4);
+        for grid in &grids {
+            let expected = sequential.solve(grid.clone())?;
+            assert_eq!(expected, parallel.solve(grid.clone())?);
+            assert_eq!(1, parallel.count_solutions(grid.clone(), 2)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_search_stops_at_limit() -> Result<(), SolverError> {
+        let solver = BacktrackingSolver::with_threads(4);
+        let solutions = solver.find_solutions(Grid::default(), 10)?;
+        assert_eq!(10, solutions.len());
+        assert!(solutions.iter().all(Grid::is_solved_correctly));
+        let distinct: HashSet<String> = solutions
+            .iter()
+            .map(|solution| format!("{:?}", solution))
+            .collect();
+        assert_eq!(10, distinct.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parallel_search_respects_node_limit() {
+        let grid = parse(include_str!("../../tests/grids/extreme.grid"));
+        let limits = SearchLimits {
+            max_nodes: Some(5),
+            ..SearchLimits::default()
+        };
+        let solver = BacktrackingSolver::with_threads(4);
+        let expected = Err(SolverError::LimitExceeded(Limit::Nodes));
+        assert_eq!(expected, solver.solve_with_limits(grid, &limits));
+    }
+}
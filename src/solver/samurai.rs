@@ -0,0 +1,194 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::time::Instant;
+
+use crate::grid::digit::Digit;
+use crate::grid::samurai::{Samurai, GRID_OFFSETS, SAMURAI_SIZE};
+use crate::grid::GRID_JOINT_SIZE;
+use crate::solver::config::SolverConfig;
+use crate::solver::{Limit, Solver, SolverError};
+
+// Solves the five grids of a Samurai jointly: each grid is deduced on its own and the digits
+// placed in the shared boxes are copied to the other grid, until no grid makes progress. Only then
+// a digit is guessed, at the cell with the fewest possible values across all grids.
+pub struct SamuraiSolver {
+    config: SolverConfig,
+    deadline: Option<Instant>,
+    nodes: usize,
+    guesses: usize,
+}
+
+impl SamuraiSolver {
+    pub fn new(config: SolverConfig) -> Self {
+        Self {
+            config,
+            deadline: None,
+            nodes: 0,
+            guesses: 0,
+        }
+    }
+
+    pub fn solve(self, samurai: Samurai) -> Result<Samurai, SolverError> {
+        let mut solutions = self.find_solutions(samurai, 1)?;
+        solutions.pop().ok_or(SolverError::NoSolution)
+    }
+
+    pub fn find_solutions(
+        mut self,
+        samurai: Samurai,
+        limit: usize,
+    ) -> Result<Vec<Samurai>, SolverError> {
+        self.deadline = self
+            .config
+            .max_duration
+            .map(|duration| Instant::now() + duration);
+        let mut solutions = Vec::new();
+        if limit > 0 {
+            self.search(samurai, &mut solutions, limit)?;
+        }
+        Ok(solutions)
+    }
+
+    fn search(
+        &mut self,
+        samurai: Samurai,
+        solutions: &mut Vec<Samurai>,
+        limit: usize,
+    ) -> Result<(), SolverError> {
+        if let Some(max_nodes) = self.config.max_nodes {
+            if self.nodes >= max_nodes {
+                return Err(SolverError::LimitExceeded(Limit::Nodes));
+            }
+        }
+        self.nodes += 1;
+        let samurai = match self.propagate(samurai) {
+            Err(SolverError::NoSolution) => return Ok(()),
+            result => result?,
+        };
+        if samurai.is_solved() {
+            solutions.push(samurai);
+            return Ok(());
+        }
+        if !self.config.allow_guessing {
+            return Err(SolverError::GuessingRequired);
+        }
+        let (x, y, values) = Self::find_guess_candidates(&samurai);
+        for value in values {
+            if let Some(max_guesses) = self.config.max_guesses {
+                if self.guesses >= max_guesses {
+                    return Err(SolverError::LimitExceeded(Limit::Guesses));
+                }
+            }
+            self.guesses += 1;
+            let mut guessed = samurai.clone();
+            guessed.set_digit(x, y, Digit::Known(value));
+            self.search(guessed, solutions, limit)?;
+            if solutions.len() >= limit {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn propagate(&self, mut samurai: Samurai) -> Result<Samurai, SolverError> {
+        // The limits are checked between the deductions of the single grids instead.
+        let config = SolverConfig {
+            max_duration: None,
+            max_passes: None,
+            ..self.config.clone()
+        };
+        loop {
+            let mut progress = false;
+            for (index, (left, top)) in GRID_OFFSETS.iter().enumerate() {
+                self.check_limits()?;
+                let grid = samurai.grids[index].clone();
+                let deduced = Solver::new(grid, config.clone()).deduce()?;
+                for y in 0..GRID_JOINT_SIZE {
+                    for x in 0..GRID_JOINT_SIZE {
+                        let digit = deduced.get_digit(x, y);
+                        let known = samurai.grids[index].get_digit(x, y);
+                        if let (Digit::Known(value), Digit::Unknown(_)) = (digit, known) {
+                            Self::place(&mut samurai, left + x, top + y, *value)?;
+                            progress = true;
+                        }
+                    }
+                }
+                samurai.grids[index] = deduced;
+            }
+            if !progress || samurai.is_solved() {
+                return Ok(samurai);
+            }
+        }
+    }
+
+    fn place(samurai: &mut Samurai, x: usize, y: usize, value: u32) -> Result<(), SolverError> {
+        for (index, grid_x, grid_y) in Samurai::locate(x, y) {
+            match samurai.grids[index].get_digit(grid_x, grid_y) {
+                Digit::Known(known) if *known != value => return Err(SolverError::NoSolution),
+                _ => samurai.grids[index].set_digit(grid_x, grid_y, Digit::Known(value)),
+            }
+        }
+        Ok(())
+    }
+
+    fn check_limits(&self) -> Result<(), SolverError> {
+        if let Some(deadline) = self.deadline {
+            if Instant::now() >= deadline {
+                return Err(SolverError::LimitExceeded(Limit::Duration));
+            }
+        }
+        Ok(())
+    }
+
+    // The possible values of a shared cell are the ones left in both grids covering it.
+    fn find_guess_candidates(samurai: &Samurai) -> (usize, usize, Vec<u32>) {
+        let mut best: Option<(usize, usize, Vec<u32>)> = None;
+        for y in 0..SAMURAI_SIZE {
+            for x in 0..SAMURAI_SIZE {
+                let mut candidates: Option<Vec<u32>> = None;
+                for (index, grid_x, grid_y) in Samurai::locate(x, y) {
+                    if let Digit::Unknown(digit) = samurai.grids[index].get_digit(grid_x, grid_y) {
+                        let values = digit.possible_values.clone();
+                        candidates = Some(match candidates {
+                            Some(candidates) => candidates
+                                .into_iter()
+                                .filter(|value| values.contains(value))
+                                .collect(),
+                            None => values,
+                        });
+                    }
+                }
+                if let Some(values) = candidates {
+                    match &best {
+                        Some((_, _, best_values)) if best_values.len() <= values.len() => {}
+                        _ => best = Some((x, y, values)),
+                    }
+                }
+            }
+        }
+        // The search only guesses in unsolved grids, so there is always an unknown digit.
+        best.unwrap()
+    }
+}
@@ -0,0 +1,448 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::borrow::Cow;
+use std::fmt::Write;
+
+use crate::grid::cell::Cell;
+use crate::grid::cell_constraint::CellConstraint;
+use crate::grid::constraints::{get_adjacent_cells, Cage};
+use crate::grid::digit::{Digit, UnknownDigit};
+use crate::grid::{Grid, GRID_CELLS, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+
+pub type CnfResult<T> = Result<T, CnfError>;
+pub type CnfError = Cow<'static, str>;
+
+pub type Literal = i32;
+pub type Clause = Vec<Literal>;
+
+pub const VARIABLES: usize = GRID_CELLS * SUBGRID_LENGTH;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cnf {
+    pub variables: usize,
+    pub clauses: Vec<Clause>,
+}
+
+impl Cnf {
+    pub fn new(variables: usize) -> Self {
+        Self {
+            variables,
+            clauses: Vec::new(),
+        }
+    }
+
+    pub fn from_grid(grid: &Grid) -> Self {
+        let mut cnf = Self::new(VARIABLES);
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                cnf.add_cell_clauses(grid.get_digit(x, y), x, y);
+            }
+        }
+        for unit in &grid.get_units() {
+            let cells = unit
+                .cells
+                .iter()
+                .map(|cell| (cell.column(), cell.row()))
+                .collect();
+            if unit.is_complete() {
+                cnf.add_unit_clauses(cells);
+            } else {
+                cnf.add_partial_unit_clauses(cells);
+            }
+        }
+        for cage in &grid.constraints.cages {
+            cnf.add_cage_clauses(cage);
+        }
+        for constraint in grid.constraints.get_cell_constraints() {
+            cnf.add_cell_constraint_clauses(constraint);
+        }
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                for cell in grid.constraints.get_visible_cells(x, y) {
+                    if (cell.row(), cell.column()) > (y, x) {
+                        cnf.add_partial_unit_clauses(vec![(x, y), (cell.column(), cell.row())]);
+                    }
+                }
+                if grid.constraints.has_pair_constraints() {
+                    cnf.add_pair_clauses(grid, x, y);
+                }
+            }
+        }
+        cnf
+    }
+
+    // Forbids every single value and every pair of values the constraint rules out on its own. This
+    // fully encodes constraints like thermometers, while the solver adds clauses for the rest of the
+    // broken assignments as they are found.
+    fn add_cell_constraint_clauses(&mut self, constraint: &dyn CellConstraint) {
+        let cells = constraint.get_cells();
+        let mut values = vec![0; GRID_CELLS];
+        let index = |cell: &Cell| cell.index();
+        for (position, cell) in cells.iter().enumerate() {
+            for value in 1..=SUBGRID_LENGTH as u32 {
+                if !constraint.allows(&values, index(cell), value) {
+                    self.add_clause(vec![-variable(cell.column(), cell.row(), value)]);
+                    continue;
+                }
+                values[index(cell)] = value;
+                for other in &cells[position + 1..] {
+                    for other_value in 1..=SUBGRID_LENGTH as u32 {
+                        if !constraint.allows(&values, index(other), other_value) {
+                            self.add_clause(vec![
+                                -variable(cell.column(), cell.row(), value),
+                                -variable(other.column(), other.row(), other_value),
+                            ]);
+                        }
+                    }
+                }
+                values[index(cell)] = 0;
+            }
+        }
+    }
+
+    // Forbids every pair of values the cell and its right and lower neighbours cannot hold.
+    fn add_pair_clauses(&mut self, grid: &Grid, x: usize, y: usize) {
+        let cell = Cell::new(x, y);
+        for other in get_adjacent_cells(x, y)
+            .into_iter()
+            .filter(|other| *other > cell)
+        {
+            for value in 1..=SUBGRID_LENGTH as u32 {
+                for other_value in 1..=SUBGRID_LENGTH as u32 {
+                    if value != other_value
+                        && !grid
+                            .constraints
+                            .allows_pair(&cell, &other, value, other_value)
+                    {
+                        self.add_clause(vec![
+                            -variable(x, y, value),
+                            -variable(other.column(), other.row(), other_value),
+                        ]);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn add_clause(&mut self, clause: Clause) {
+        self.clauses.push(clause);
+    }
+
+    pub fn to_dimacs(&self) -> String {
+        let mut result = format!("p cnf {} {}\n", self.variables, self.clauses.len());
+        for clause in &self.clauses {
+            for literal in clause {
+                write!(result, "{} ", literal).unwrap();
+            }
+            result.push_str("0\n");
+        }
+        result
+    }
+
+    pub fn parse_dimacs(syntax: &str) -> CnfResult<Self> {
+        let mut header = None;
+        let mut clauses = Vec::new();
+        let mut clause = Vec::new();
+        for line in syntax.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('c') || line.starts_with('%') {
+                continue;
+            }
+            if line.starts_with('p') {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                match fields.as_slice() {
+                    ["p", "cnf", variables, clauses] => {
+                        let variables = parse_number(variables)?;
+                        let clauses = parse_number(clauses)?;
+                        header = Some((variables, clauses));
+                    }
+                    _ => return Err(format!("Invalid problem line: '{}'", line).into()),
+                }
+                continue;
+            }
+            if header.is_none() {
+                return Err("Expected problem line before clauses".into());
+            }
+            for literal in parse_literals(line)? {
+                if literal == 0 {
+                    clauses.push(std::mem::take(&mut clause));
+                } else {
+                    clause.push(literal);
+                }
+            }
+        }
+        if !clause.is_empty() {
+            clauses.push(clause);
+        }
+        let (variables, expected_clauses) = header.ok_or("Missing problem line")?;
+        if clauses.len() != expected_clauses {
+            return Err(format!(
+                "Expected {} clauses, found {}",
+                expected_clauses,
+                clauses.len()
+            )
+            .into());
+        }
+        let out_of_range = clauses
+            .iter()
+            .flatten()
+            .find(|literal| literal.unsigned_abs() as usize > variables);
+        if let Some(literal) = out_of_range {
+            return Err(format!("Literal {} exceeds {} variables", literal, variables).into());
+        }
+        Ok(Self { variables, clauses })
+    }
+
+    fn add_cell_clauses(&mut self, digit: &Digit, x: usize, y: usize) {
+        match digit {
+            Digit::Known(value) => self.add_clause(vec![variable(x, y, *value)]),
+            Digit::Unknown(UnknownDigit { possible_values }) if !possible_values.is_empty() => {
+                for value in 1..=SUBGRID_LENGTH as u32 {
+                    if !possible_values.contains(&value) {
+                        self.add_clause(vec![-variable(x, y, value)]);
+                    }
+                }
+            }
+            Digit::Unknown(_) => {}
+        }
+        let literals = (1..=SUBGRID_LENGTH as u32)
+            .map(|value| variable(x, y, value))
+            .collect();
+        self.add_exactly_one(literals);
+    }
+
+    fn add_unit_clauses(&mut self, cells: Vec<(usize, usize)>) {
+        for value in 1..=SUBGRID_LENGTH as u32 {
+            let literals = cells.iter().map(|&(x, y)| variable(x, y, value)).collect();
+            self.add_exactly_one(literals);
+        }
+    }
+
+    fn add_partial_unit_clauses(&mut self, cells: Vec<(usize, usize)>) {
+        for value in 1..=SUBGRID_LENGTH as u32 {
+            let literals: Vec<_> = cells.iter().map(|&(x, y)| variable(x, y, value)).collect();
+            for (i, first) in literals.iter().enumerate() {
+                for second in &literals[i + 1..] {
+                    self.add_clause(vec![-first, -second]);
+                }
+            }
+        }
+    }
+
+    // Each cage gets a new variable per value, implied by the value being placed in any of its
+    // cells. Every set of values that fills the cage, but does not add up to its sum, is forbidden.
+    fn add_cage_clauses(&mut self, cage: &Cage) {
+        let first_variable = self.variables;
+        self.variables += SUBGRID_LENGTH;
+        let used = |value: u32| (first_variable + value as usize) as Literal;
+        for value in 1..=SUBGRID_LENGTH as u32 {
+            for cell in &cage.cells {
+                self.add_clause(vec![
+                    -variable(cell.column(), cell.row(), value),
+                    used(value),
+                ]);
+            }
+        }
+        let combinations = cage.combinations();
+        for set in (0..1u16 << SUBGRID_LENGTH).map(|set| set << 1) {
+            if set.count_ones() as usize != cage.cells.len() || combinations.contains(&set) {
+                continue;
+            }
+            let clause = (1..=SUBGRID_LENGTH as u32)
+                .filter(|value| set & (1 << value) != 0)
+                .map(|value| -used(value))
+                .collect();
+            self.add_clause(clause);
+        }
+    }
+
+    fn add_exactly_one(&mut self, literals: Vec<Literal>) {
+        for (i, first) in literals.iter().enumerate() {
+            for second in &literals[i + 1..] {
+                self.add_clause(vec![-first, -second]);
+            }
+        }
+        self.add_clause(literals);
+    }
+}
+
+// Returns a clause forbidding the values that break the constraint, reduced to the values that
+// break it on their own, or nothing when the values are consistent with it.
+pub fn explain_broken_constraint(
+    constraint: &dyn CellConstraint,
+    values: &[u32],
+) -> Option<Clause> {
+    if constraint.is_feasible(values) {
+        return None;
+    }
+    let mut values = values.to_vec();
+    let mut clause = Vec::new();
+    for cell in constraint.get_cells() {
+        let index = cell.index();
+        let value = values[index];
+        values[index] = 0;
+        if constraint.is_feasible(&values) {
+            values[index] = value;
+            clause.push(-variable(cell.column(), cell.row(), value));
+        }
+    }
+    Some(clause)
+}
+
+pub fn variable(x_joint: usize, y_joint: usize, value: u32) -> Literal {
+    let cell = Cell::new(x_joint, y_joint);
+    (cell.index() * SUBGRID_LENGTH + value as usize) as Literal
+}
+
+pub fn decode_model(model: &[Literal]) -> CnfResult<Grid> {
+    let mut values = vec![None; GRID_CELLS];
+    for &literal in model.iter().filter(|&&literal| literal > 0) {
+        let index = literal as usize - 1;
+        if index >= VARIABLES {
+            continue;
+        }
+        let cell = Cell::from_index(index / SUBGRID_LENGTH);
+        let value = (index % SUBGRID_LENGTH) as u32 + 1;
+        if let Some(previous) = values[cell.index()].replace(value) {
+            return Err(format!(
+                "Cell r{}c{} is assigned both {} and {}",
+                cell.row() + 1,
+                cell.column() + 1,
+                previous,
+                value
+            )
+            .into());
+        }
+    }
+    let mut grid = Grid::default();
+    for (cell, value) in Cell::all().zip(values) {
+        let value = value.ok_or_else(|| {
+            format!(
+                "Cell r{}c{} has no value",
+                cell.row() + 1,
+                cell.column() + 1
+            )
+        })?;
+        grid.set_cell(cell, Digit::Known(value));
+    }
+    Ok(grid)
+}
+
+pub fn parse_model(syntax: &str) -> CnfResult<Vec<Literal>> {
+    let mut model = Vec::new();
+    for line in syntax.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('c') {
+            continue;
+        }
+        if line.starts_with('s') {
+            if line.contains("UNSAT") {
+                return Err("The model is unsatisfiable".into());
+            }
+            continue;
+        }
+        let line = line.strip_prefix('v').unwrap_or(line);
+        model.extend(
+            parse_literals(line)?
+                .into_iter()
+                .filter(|&literal| literal != 0),
+        );
+    }
+    Ok(model)
+}
+
+fn parse_literals(line: &str) -> CnfResult<Vec<Literal>> {
+    line.split_whitespace()
+        .map(|field| {
+            field
+                .parse()
+                .map_err(|_| format!("Invalid literal: '{}'", field).into())
+        })
+        .collect()
+}
+
+fn parse_number(field: &str) -> CnfResult<usize> {
+    field
+        .parse()
+        .map_err(|_| format!("Invalid number: '{}'", field).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::parser::Parser;
+
+    use super::*;
+
+    #[test]
+    fn test_dimacs_round_trip() {
+        let grid = Parser::new()
+            .parse(include_str!("../../../tests/grids/easy.grid").to_owned())
+            .unwrap();
+        let cnf = Cnf::from_grid(&grid);
+        assert_eq!(VARIABLES, cnf.variables);
+        let dimacs = cnf.to_dimacs();
+        assert!(dimacs.starts_with(&format!("p cnf 729 {}\n", cnf.clauses.len())));
+        assert_eq!(Ok(cnf), Cnf::parse_dimacs(&dimacs));
+    }
+
+    #[test]
+    fn test_candidate_eliminations_are_encoded() {
+        let mut grid = Grid::default();
+        grid.set_digit(4, 2, Digit::Unknown(UnknownDigit::new(vec![3, 7])));
+        let cnf = Cnf::from_grid(&grid);
+        let eliminated: Vec<&Clause> = cnf
+            .clauses
+            .iter()
+            .filter(|clause| clause.len() == 1)
+            .collect();
+        assert_eq!(7, eliminated.len());
+        assert!(eliminated.contains(&&vec![-variable(4, 2, 1)]));
+        assert!(!eliminated.contains(&&vec![-variable(4, 2, 3)]));
+    }
+
+    #[test]
+    fn test_model_is_decoded() {
+        let model = parse_model("s SATISFIABLE\nv -1 2 -3 4\nv 0\n").unwrap();
+        assert_eq!(vec![-1, 2, -3, 4], model);
+        assert!(decode_model(&model).is_err());
+
+        let syntax =
+            "534678912672195348198342567859761423426853791713924856961537284287419635345286179";
+        let grid = Parser::new().parse_line(syntax.to_owned()).unwrap();
+        let model: Vec<Literal> = Cell::all()
+            .map(|cell| match grid.get_cell(cell) {
+                Digit::Known(value) => variable(cell.column(), cell.row(), *value),
+                Digit::Unknown(_) => unreachable!(),
+            })
+            .collect();
+        assert_eq!(Ok(grid), decode_model(&model));
+    }
+
+    #[test]
+    fn test_invalid_dimacs_is_rejected() {
+        assert!(Cnf::parse_dimacs("1 2 0\n").is_err());
+        assert!(Cnf::parse_dimacs("p cnf 2 2\n1 2 0\n").is_err());
+        assert!(Cnf::parse_dimacs("p cnf 2 1\n1 3 0\n").is_err());
+        assert!(Cnf::parse_dimacs("p cnf 2 1\n1 x 0\n").is_err());
+    }
+}
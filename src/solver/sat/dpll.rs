@@ -0,0 +1,271 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::solver::backend::{LimitTracker, SearchLimits};
+use crate::solver::sat::cnf::{Clause, Cnf, Literal};
+use crate::solver::SolverError;
+
+struct Decision {
+    trail_length: usize,
+    literal: Literal,
+    flipped: bool,
+}
+
+pub struct Dpll {
+    clauses: Vec<Clause>,
+    watches: Vec<Vec<usize>>,
+    assignment: Vec<Option<bool>>,
+    trail: Vec<Literal>,
+    decisions: Vec<Decision>,
+    propagated: usize,
+}
+
+impl Dpll {
+    pub fn new(cnf: &Cnf) -> Self {
+        let mut result = Self {
+            clauses: Vec::new(),
+            watches: vec![Vec::new(); cnf.variables * 2],
+            assignment: vec![None; cnf.variables],
+            trail: Vec::new(),
+            decisions: Vec::new(),
+            propagated: 0,
+        };
+        for clause in &cnf.clauses {
+            result.add_clause(clause.clone());
+        }
+        result
+    }
+
+    pub fn add_clause(&mut self, clause: Clause) {
+        let index = self.clauses.len();
+        if clause.len() >= 2 {
+            self.watches[watch_index(clause[0])].push(index);
+            self.watches[watch_index(clause[1])].push(index);
+        }
+        self.clauses.push(clause);
+    }
+
+    pub fn solve(&mut self, limits: &SearchLimits) -> Result<Option<Vec<Literal>>, SolverError> {
+        self.solve_with_tracker(&mut LimitTracker::new(limits))
+    }
+
+    pub(crate) fn solve_with_tracker(
+        &mut self,
+        tracker: &mut LimitTracker,
+    ) -> Result<Option<Vec<Literal>>, SolverError> {
+        self.reset();
+        if !self.assign_unit_clauses() || !self.propagate() {
+            return Ok(None);
+        }
+        loop {
+            tracker.visit_node()?;
+            let literal = match self.choose_literal() {
+                Some(literal) => literal,
+                None => return Ok(Some(self.model())),
+            };
+            self.decisions.push(Decision {
+                trail_length: self.trail.len(),
+                literal,
+                flipped: false,
+            });
+            self.assign(literal);
+            while !self.propagate() {
+                if !self.backtrack() {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.assignment.iter_mut().for_each(|value| *value = None);
+        self.trail.clear();
+        self.decisions.clear();
+        self.propagated = 0;
+    }
+
+    fn assign_unit_clauses(&mut self) -> bool {
+        for index in 0..self.clauses.len() {
+            match self.clauses[index].as_slice() {
+                [] => return false,
+                [literal] => {
+                    let literal = *literal;
+                    match self.value(literal) {
+                        Some(false) => return false,
+                        Some(true) => {}
+                        None => self.assign(literal),
+                    }
+                }
+                _ => {}
+            }
+        }
+        true
+    }
+
+    fn backtrack(&mut self) -> bool {
+        while let Some(decision) = self.decisions.pop() {
+            self.undo(decision.trail_length);
+            if !decision.flipped {
+                self.decisions.push(Decision {
+                    flipped: true,
+                    literal: -decision.literal,
+                    ..decision
+                });
+                self.assign(-decision.literal);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn undo(&mut self, trail_length: usize) {
+        for literal in self.trail.drain(trail_length..) {
+            self.assignment[variable_index(literal)] = None;
+        }
+        self.propagated = trail_length;
+    }
+
+    fn propagate(&mut self) -> bool {
+        while self.propagated < self.trail.len() {
+            let false_literal = -self.trail[self.propagated];
+            self.propagated += 1;
+            let watchers = std::mem::take(&mut self.watches[watch_index(false_literal)]);
+            let mut kept = Vec::with_capacity(watchers.len());
+            let mut conflict = false;
+            for (position, &index) in watchers.iter().enumerate() {
+                if conflict {
+                    kept.extend_from_slice(&watchers[position..]);
+                    break;
+                }
+                if self.clauses[index][0] == false_literal {
+                    self.clauses[index].swap(0, 1);
+                }
+                let other = self.clauses[index][0];
+                if self.value(other) == Some(true) {
+                    kept.push(index);
+                    continue;
+                }
+                let replacement = (2..self.clauses[index].len())
+                    .find(|&k| self.value(self.clauses[index][k]) != Some(false));
+                if let Some(k) = replacement {
+                    self.clauses[index].swap(1, k);
+                    let watched = self.clauses[index][1];
+                    self.watches[watch_index(watched)].push(index);
+                    continue;
+                }
+                kept.push(index);
+                match self.value(other) {
+                    Some(false) => conflict = true,
+                    _ => self.assign(other),
+                }
+            }
+            self.watches[watch_index(false_literal)] = kept;
+            if conflict {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn choose_literal(&self) -> Option<Literal> {
+        let mut best: Option<(usize, Literal)> = None;
+        for clause in &self.clauses {
+            if clause
+                .iter()
+                .any(|&literal| self.value(literal) == Some(true))
+            {
+                continue;
+            }
+            let mut unassigned = clause
+                .iter()
+                .filter(|&&literal| self.value(literal).is_none());
+            let first = match unassigned.next() {
+                Some(&literal) => literal,
+                None => continue,
+            };
+            let count = 1 + unassigned.count();
+            if best.map_or(true, |(best_count, _)| count < best_count) {
+                best = Some((count, first));
+                if count == 2 {
+                    break;
+                }
+            }
+        }
+        best.map(|(_, literal)| literal)
+    }
+
+    fn model(&self) -> Vec<Literal> {
+        self.assignment
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let variable = index as Literal + 1;
+                if *value == Some(true) {
+                    variable
+                } else {
+                    -variable
+                }
+            })
+            .collect()
+    }
+
+    fn assign(&mut self, literal: Literal) {
+        self.assignment[variable_index(literal)] = Some(literal > 0);
+        self.trail.push(literal);
+    }
+
+    fn value(&self, literal: Literal) -> Option<bool> {
+        self.assignment[variable_index(literal)].map(|value| value == (literal > 0))
+    }
+}
+
+fn variable_index(literal: Literal) -> usize {
+    literal.unsigned_abs() as usize - 1
+}
+
+fn watch_index(literal: Literal) -> usize {
+    variable_index(literal) * 2 + (literal < 0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solve(syntax: &str) -> Option<Vec<Literal>> {
+        let cnf = Cnf::parse_dimacs(syntax).unwrap();
+        Dpll::new(&cnf).solve(&SearchLimits::default()).unwrap()
+    }
+
+    #[test]
+    fn test_satisfiable_formula_has_model() {
+        let model = solve("p cnf 3 4\n1 2 0\n-1 3 0\n-3 -2 0\n-2 0\n").unwrap();
+        assert_eq!(vec![1, -2, 3], model);
+    }
+
+    #[test]
+    fn test_unsatisfiable_formula_has_no_model() {
+        assert_eq!(None, solve("p cnf 2 4\n1 2 0\n-1 2 0\n1 -2 0\n-1 -2 0\n"));
+        assert_eq!(None, solve("p cnf 1 2\n1 0\n-1 0\n"));
+    }
+}
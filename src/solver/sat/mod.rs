@@ -0,0 +1,90 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use crate::grid::Grid;
+use crate::solver::backend::{Engine, LimitTracker, SearchLimits, SolverBackend};
+use crate::solver::sat::cnf::{decode_model, explain_broken_constraint, Cnf, VARIABLES};
+use crate::solver::sat::dpll::Dpll;
+use crate::solver::SolverError;
+
+pub mod cnf;
+pub mod dpll;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SatSolver;
+
+impl SatSolver {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl SolverBackend for SatSolver {
+    fn name(&self) -> &'static str {
+        Engine::Sat.name()
+    }
+
+    fn find_solutions_with_limits(
+        &self,
+        grid: Grid,
+        limit: usize,
+        limits: &SearchLimits,
+    ) -> Result<Vec<Grid>, SolverError> {
+        let cnf = Cnf::from_grid(&grid);
+        let mut dpll = Dpll::new(&cnf);
+        let mut tracker = LimitTracker::new(limits);
+        let mut solutions = Vec::new();
+        while solutions.len() < limit {
+            let model = match dpll.solve_with_tracker(&mut tracker)? {
+                Some(model) => model,
+                None => break,
+            };
+            // Only cell variables are blocked, as auxiliary ones may differ for the same solution.
+            let blocking_clause = model
+                .iter()
+                .filter(|&&literal| literal > 0 && literal as usize <= VARIABLES)
+                .map(|&literal| -literal)
+                .collect();
+            let mut solution = decode_model(&model).map_err(|_| SolverError::NoSolution)?;
+            // Cell constraints are only partially encoded, so a model breaking one of them is
+            // ruled out by a new clause and the search goes on.
+            let values = solution.get_values();
+            let explanations = grid
+                .constraints
+                .get_cell_constraints()
+                .filter_map(|constraint| explain_broken_constraint(constraint, &values))
+                .collect::<Vec<_>>();
+            if !explanations.is_empty() {
+                explanations
+                    .into_iter()
+                    .for_each(|clause| dpll.add_clause(clause));
+                continue;
+            }
+            dpll.add_clause(blocking_clause);
+            solution.constraints = grid.constraints.clone();
+            solutions.push(solution);
+        }
+        Ok(solutions)
+    }
+}
@@ -0,0 +1,93 @@
+/*
+ * MIT License
+ *
+ * Copyright (c) 2021 Piotr Dobiech
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy
+ * of this software and associated documentation files (the "Software"), to deal
+ * in the Software without restriction, including without limitation the rights
+ * to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+ * copies of the Software, and to permit persons to whom the Software is
+ * furnished to do so, subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+ * FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+ * AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+ * LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+ * OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+ * SOFTWARE.
+ */
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::solver::config::Strategy;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SolveStats {
+    pub passes: usize,
+    pub placements: BTreeMap<Strategy, usize>,
+    pub eliminations: BTreeMap<Strategy, usize>,
+    pub guesses: usize,
+    pub backtracks: usize,
+    pub max_depth: usize,
+    pub elapsed: Duration,
+}
+
+impl SolveStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn placements(&self, strategy: Strategy) -> usize {
+        self.placements.get(&strategy).copied().unwrap_or(0)
+    }
+
+    pub fn eliminations(&self, strategy: Strategy) -> usize {
+        self.eliminations.get(&strategy).copied().unwrap_or(0)
+    }
+
+    pub fn total_placements(&self) -> usize {
+        self.placements.values().sum()
+    }
+
+    pub fn total_eliminations(&self) -> usize {
+        self.eliminations.values().sum()
+    }
+
+    pub(crate) fn add_placements(&mut self, strategy: Strategy, count: usize) {
+        if count > 0 {
+            *self.placements.entry(strategy).or_insert(0) += count;
+        }
+    }
+
+    pub(crate) fn add_eliminations(&mut self, strategy: Strategy, count: usize) {
+        if count > 0 {
+            *self.eliminations.entry(strategy).or_insert(0) += count;
+        }
+    }
+}
+
+impl Display for SolveStats {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(formatter, "Passes: {}", self.passes)?;
+        writeln!(formatter, "Placements: {}", self.total_placements())?;
+        for (strategy, count) in &self.placements {
+            writeln!(formatter, "  {}: {}", strategy.name(), count)?;
+        }
+        writeln!(formatter, "Eliminations: {}", self.total_eliminations())?;
+        for (strategy, count) in &self.eliminations {
+            writeln!(formatter, "  {}: {}", strategy.name(), count)?;
+        }
+        writeln!(formatter, "Guesses: {}", self.guesses)?;
+        writeln!(formatter, "Backtracks: {}", self.backtracks)?;
+        writeln!(formatter, "Max depth: {}", self.max_depth)?;
+        writeln!(formatter, "Elapsed: {:?}", self.elapsed)
+    }
+}
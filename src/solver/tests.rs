@@ -22,11 +22,24 @@
  * SOFTWARE.
  */
 
+use crate::grid::constraints::{Constraints, Unit, UnitKind};
 use crate::grid::digit::UnknownDigit;
 use crate::grid::Subgrid;
 
 use super::*;
 
+// The grids below only keep the candidates consistent in the subgrids and the lines of one
+// direction.
+fn get_subgrid_and_line_units(grid: &Grid, kind: UnitKind) -> Units {
+    let units = grid
+        .get_units()
+        .units
+        .into_iter()
+        .filter(|unit| unit.kind == UnitKind::Subgrid || unit.kind == kind)
+        .collect();
+    Units::new(units)
+}
+
 #[test]
 fn test_eliminate_impossible_possible_values_in_columns() {
     let grid = Grid::new(vec![
@@ -111,8 +124,9 @@ fn test_eliminate_impossible_possible_values_in_columns() {
         Subgrid::default(),
         Subgrid::default(),
     ]);
-    let mut solver = Solver::new(grid);
-    solver.eliminate_impossible_possible_values_in_columns();
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    let units = get_subgrid_and_line_units(&solver.grid, UnitKind::Column);
+    solver.eliminate_locked_candidates(&units);
     assert_eq!(expected, solver.grid);
 }
 
@@ -200,8 +214,9 @@ fn test_eliminate_impossible_possible_values_in_columns_full() {
         Subgrid::default(),
         Subgrid::default(),
     ]);
-    let mut solver = Solver::new(grid);
-    solver.eliminate_impossible_possible_values_in_columns();
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    let units = get_subgrid_and_line_units(&solver.grid, UnitKind::Column);
+    solver.eliminate_locked_candidates(&units);
     assert_eq!(expected, solver.grid);
 }
 
@@ -289,8 +304,9 @@ fn test_eliminate_impossible_possible_values_in_rows() {
         Subgrid::default(),
         Subgrid::default(),
     ]);
-    let mut solver = Solver::new(grid);
-    solver.eliminate_impossible_possible_values_in_rows();
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    let units = get_subgrid_and_line_units(&solver.grid, UnitKind::Row);
+    solver.eliminate_locked_candidates(&units);
     assert_eq!(expected, solver.grid);
 }
 
@@ -378,7 +394,189 @@ fn test_eliminate_impossible_possible_values_in_rows_full() {
         Subgrid::default(),
         Subgrid::default(),
     ]);
-    let mut solver = Solver::new(grid);
-    solver.eliminate_impossible_possible_values_in_rows();
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    let units = get_subgrid_and_line_units(&solver.grid, UnitKind::Row);
+    solver.eliminate_locked_candidates(&units);
     assert_eq!(expected, solver.grid);
 }
+
+// Sets the candidates of the given cells, in the order of the cells.
+fn set_candidates(grid: &mut Grid, cells: &[Cell], candidates: &[Vec<u32>]) {
+    for (cell, values) in cells.iter().zip(candidates) {
+        let digit = Digit::Unknown(UnknownDigit::new(values.clone()));
+        grid.set_digit(cell.column(), cell.row(), digit);
+    }
+}
+
+fn get_subset_candidates() -> Vec<Vec<u32>> {
+    vec![
+        vec![1, 2],
+        vec![1, 2],
+        vec![1, 2, 3],
+        vec![3, 4, 8, 9],
+        vec![5, 8, 9],
+        vec![3, 4, 5, 6, 7],
+        vec![3, 4, 5, 6, 7],
+        vec![3, 4, 5, 6, 7],
+        vec![3, 4, 5, 6, 7],
+    ]
+}
+
+#[test]
+fn test_naked_and_hidden_subsets_eliminate_values() {
+    let mut grid = Grid::default();
+    let cells = Cell::row_cells(0).collect::<Vec<_>>();
+    set_candidates(&mut grid, &cells, &get_subset_candidates());
+    let units = Units::new(vec![Unit::new(UnitKind::Row, 0, cells)]);
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    solver.eliminate_impossible_possible_values_in_subsets(&units);
+    let expected = [vec![3], vec![8, 9], vec![8, 9]];
+    for (x, values) in (2..5).zip(expected.iter()) {
+        let digit = Digit::Unknown(UnknownDigit::new(values.clone()));
+        assert_eq!(&digit, solver.grid.get_digit(x, 0));
+    }
+    assert_eq!(
+        &Digit::Unknown(UnknownDigit::new(vec![1, 2])),
+        solver.grid.get_digit(0, 0)
+    );
+}
+
+#[test]
+fn test_subsets_are_found_in_windows() {
+    let constraints = Constraints {
+        windows: true,
+        ..Constraints::default()
+    };
+    let mut grid = Grid::from_cells(Grid::default().digits, constraints);
+    let window = grid
+        .get_units()
+        .units
+        .into_iter()
+        .find(|unit| unit.kind == UnitKind::Window)
+        .unwrap();
+    let cells = window.cells.clone();
+    set_candidates(&mut grid, &cells, &get_subset_candidates());
+    let units = Units::new(vec![window]);
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    solver.eliminate_impossible_possible_values_in_subsets(&units);
+    let digit = Digit::Unknown(UnknownDigit::new(vec![3]));
+    assert_eq!(
+        &digit,
+        solver.grid.get_digit(cells[2].column(), cells[2].row())
+    );
+}
+
+#[test]
+fn test_grid_without_solution_is_rejected() {
+    let grid = Grid::new(vec![
+        Subgrid::from_digits(vec![1, 2, 3, 0, 0, 0, 0, 0, 0]),
+        Subgrid::from_digits(vec![4, 5, 6, 0, 0, 0, 0, 0, 0]),
+        Subgrid::from_digits(vec![7, 8, 0, 0, 0, 0, 0, 0, 0]),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::from_digits(vec![0, 0, 9, 0, 0, 0, 0, 0, 0]),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+    ]);
+    let solver = Solver::new(grid, SolverConfig::default());
+    assert_eq!(Err(SolverError::NoSolution), solver.solve());
+}
+
+#[test]
+fn test_disabled_strategies_are_not_used() {
+    let grid = Grid::new(vec![
+        Subgrid::from_digits(vec![1, 2, 3, 4, 5, 6, 7, 8, 0]),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+    ]);
+    let config = SolverConfig {
+        strategies: vec![],
+        ..SolverConfig::deduction_only()
+    };
+    let solver = Solver::new(grid, config);
+    assert_eq!(Err(SolverError::GuessingRequired), solver.solve());
+}
+
+#[test]
+fn test_stats_are_collected() {
+    let grid = Grid::new(vec![
+        Subgrid::from_digits(vec![1, 2, 3, 4, 5, 6, 7, 8, 0]),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+        Subgrid::default(),
+    ]);
+    let config = SolverConfig {
+        strategies: vec![Strategy::NakedSingles],
+        max_passes: Some(1),
+        ..SolverConfig::deduction_only()
+    };
+    let solver = Solver::new(grid, config);
+    let (result, stats) = solver.solve_with_stats();
+    assert_eq!(Err(SolverError::LimitExceeded(Limit::Passes)), result);
+    assert_eq!(1, stats.passes);
+    assert_eq!(1, stats.placements(Strategy::NakedSingles));
+    assert_eq!(0, stats.placements(Strategy::HiddenSingles));
+    assert_eq!(0, stats.guesses);
+}
+
+#[test]
+fn test_guesses_are_counted_in_stats() {
+    let solver = Solver::new(Grid::default(), SolverConfig::default());
+    let (result, stats) = solver.solve_with_stats();
+    assert!(result.unwrap().is_solved_correctly());
+    assert!(stats.guesses > 0);
+    assert!(stats.max_depth > 0);
+    assert!(stats.total_placements() > 0);
+    assert!(stats.passes >= stats.guesses);
+}
+
+#[test]
+fn test_guessing_can_be_disabled() {
+    let solver = Solver::new(Grid::default(), SolverConfig::deduction_only());
+    assert_eq!(Err(SolverError::GuessingRequired), solver.solve());
+    let solver = Solver::new(Grid::default(), SolverConfig::default());
+    assert!(solver.solve().unwrap().is_solved_correctly());
+}
+
+#[test]
+fn test_pass_and_guess_limits_are_enforced() {
+    let config = SolverConfig {
+        max_passes: Some(1),
+        ..SolverConfig::default()
+    };
+    let solver = Solver::new(Grid::default(), config);
+    assert_eq!(
+        Err(SolverError::LimitExceeded(Limit::Passes)),
+        solver.solve()
+    );
+    let config = SolverConfig {
+        max_guesses: Some(0),
+        ..SolverConfig::default()
+    };
+    let solver = Solver::new(Grid::default(), config);
+    assert_eq!(
+        Err(SolverError::LimitExceeded(Limit::Guesses)),
+        solver.solve()
+    );
+}
+
+#[test]
+fn test_guessing_finds_several_solutions() {
+    let solver = Solver::new(Grid::default(), SolverConfig::default());
+    let solutions = solver.find_solutions(2).unwrap();
+    assert_eq!(2, solutions.len());
+    assert_ne!(solutions[0], solutions[1]);
+    assert!(solutions.iter().all(Grid::is_solved_correctly));
+}
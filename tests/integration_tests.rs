@@ -23,12 +23,51 @@
  */
 
 use std::borrow::Cow;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use sudoku_solver::grid::constraints::UnitKind;
+use sudoku_solver::grid::digit::Digit;
+use sudoku_solver::grid::generator::Generator;
 use sudoku_solver::grid::parser::Parser;
-use sudoku_solver::solver::Solver;
+use sudoku_solver::grid::transform::Transformation;
+use sudoku_solver::grid::visibility::Relation;
+use sudoku_solver::grid::{Grid, GRID_JOINT_SIZE, SUBGRID_LENGTH};
+use sudoku_solver::solver::backend::{Engine, SearchLimits};
+use sudoku_solver::solver::cancellation::CancellationToken;
+use sudoku_solver::solver::config::{SolverConfig, Strategy};
+use sudoku_solver::solver::progress::Progress;
+use sudoku_solver::solver::samurai::SamuraiSolver;
+use sudoku_solver::solver::sat::cnf::{decode_model, parse_model, Cnf};
+use sudoku_solver::solver::sat::dpll::Dpll;
+use sudoku_solver::solver::{Limit, Solver, SolverError};
 
 type TestResult = Result<(), TestError>;
 type TestError = Cow<'static, str>;
 
+// Checks that every engine finds the same single, valid solution of the grid and returns it.
+fn assert_unique_solution_by_all_engines(grid: &Grid) -> Result<Grid, TestError> {
+    let mut solution: Option<Grid> = None;
+    for engine in &Engine::ALL {
+        let solver = engine.create(SolverConfig::default());
+        let mut solutions = solver.find_solutions(grid.clone(), 2)?;
+        assert_eq!(1, solutions.len(), "{}", solver.name());
+        let found = solutions.remove(0);
+        assert!(found.is_solved_correctly(), "{}", solver.name());
+        assert!(found.validate().is_valid(), "{}", solver.name());
+        if let Some(solution) = &solution {
+            assert_eq!(
+                solution.get_values(),
+                found.get_values(),
+                "{}",
+                solver.name()
+            );
+        }
+        solution.get_or_insert(found);
+    }
+    Ok(solution.unwrap())
+}
+
 #[test]
 fn test_easy_board_is_solved() -> TestResult {
     let grid_syntax = include_str!("grids/easy.grid");
@@ -48,17 +87,402 @@ fn test_hard_grid_is_solved_correctly() -> TestResult {
 }
 
 #[test]
-#[ignore]
 fn test_extreme_grid_is_solved_correctly() -> TestResult {
     let grid_syntax = include_str!("grids/extreme.grid");
     test_grid_is_solved_correctly(grid_syntax)
 }
 
+#[test]
+fn test_guessing_grid_cannot_be_solved_without_guessing() -> TestResult {
+    let grid_syntax = include_str!("grids/guessing.grid").to_owned();
+    let grid = Parser::new().parse(grid_syntax)?;
+    let solver = Solver::new(grid, SolverConfig::deduction_only());
+    assert_eq!(Err(SolverError::GuessingRequired), solver.solve());
+    Ok(())
+}
+
+#[test]
+fn test_pass_limit_is_enforced() -> TestResult {
+    let grid_syntax = include_str!("grids/guessing.grid").to_owned();
+    let grid = Parser::new().parse(grid_syntax)?;
+    let config = SolverConfig {
+        max_passes: Some(1),
+        ..SolverConfig::default()
+    };
+    let solver = Solver::new(grid, config);
+    let expected = Err(SolverError::LimitExceeded(Limit::Passes));
+    assert_eq!(expected, solver.solve());
+    Ok(())
+}
+
+#[test]
+fn test_cancelled_solver_returns_error() -> TestResult {
+    let grid_syntax = include_str!("grids/guessing.grid").to_owned();
+    let grid = Parser::new().parse(grid_syntax)?;
+    let cancellation_token = CancellationToken::new();
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    solver.set_cancellation_token(cancellation_token.clone());
+    let callback_token = cancellation_token.clone();
+    solver.set_progress_callback(move |progress| {
+        if progress.nodes > 1 {
+            callback_token.cancel();
+        }
+    });
+    let handle = thread::spawn(move || solver.solve());
+    let result = handle.join().map_err(|_| "Solver thread has panicked")?;
+    assert_eq!(Err(SolverError::Cancelled), result);
+    assert!(cancellation_token.is_cancelled());
+    Ok(())
+}
+
+#[test]
+fn test_progress_is_reported() -> TestResult {
+    let grid_syntax = include_str!("grids/guessing.grid").to_owned();
+    let grid = Parser::new().parse(grid_syntax)?;
+    let reports = Arc::new(Mutex::new(Vec::<Progress>::new()));
+    let mut solver = Solver::new(grid, SolverConfig::default());
+    let callback_reports = Arc::clone(&reports);
+    solver.set_progress_callback(move |progress| {
+        callback_reports.lock().unwrap().push(*progress);
+    });
+    solver.solve()?;
+    let reports = reports.lock().unwrap();
+    assert!(reports.len() > 1);
+    assert!(reports
+        .windows(2)
+        .all(|pair| pair[0].nodes <= pair[1].nodes));
+    assert!(reports.iter().any(|progress| progress.depth > 0));
+    assert_eq!(81, reports.last().unwrap().filled_cells);
+    Ok(())
+}
+
+#[test]
+fn test_engines_find_the_same_solutions() -> TestResult {
+    let grid_syntaxes = [
+        include_str!("grids/easy.grid"),
+        include_str!("grids/medium.grid"),
+        include_str!("grids/hard.grid"),
+        include_str!("grids/extreme.grid"),
+    ];
+    for grid_syntax in &grid_syntaxes {
+        let grid = Parser::new().parse(grid_syntax.to_string())?;
+        let expected = Engine::Propagation
+            .create(SolverConfig::default())
+            .solve(grid.clone())?;
+        for engine in &Engine::ALL {
+            let solver = engine.create(SolverConfig::default());
+            assert_eq!(expected, solver.solve(grid.clone())?, "{}", solver.name());
+            assert_eq!(
+                1,
+                solver.count_solutions(grid.clone(), 2)?,
+                "{}",
+                solver.name()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_diagonal_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/diagonal.grid").to_owned())?;
+    assert!(grid.constraints.diagonal);
+    let solution = assert_unique_solution_by_all_engines(&grid)?;
+    for anti in [false, true] {
+        let mut values: Vec<&Digit> = (0..GRID_JOINT_SIZE)
+            .map(|y| {
+                let x = if anti { GRID_JOINT_SIZE - 1 - y } else { y };
+                solution.get_digit(x, y)
+            })
+            .collect();
+        values.sort();
+        values.dedup();
+        assert_eq!(GRID_JOINT_SIZE, values.len());
+    }
+    let mut classic = grid;
+    classic.constraints.diagonal = false;
+    let solver = Engine::DancingLinks.create(SolverConfig::default());
+    assert_eq!(2, solver.count_solutions(classic, 2)?);
+    Ok(())
+}
+
+#[test]
+fn test_windoku_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/windoku.grid").to_owned())?;
+    assert!(grid.constraints.windows);
+    let expected = Parser::new().parse_line(
+        "438591762571426893962873154195384276624715938387269541849652317253147689716938425"
+            .to_owned(),
+    )?;
+    let solution = assert_unique_solution_by_all_engines(&grid)?;
+    assert_eq!(expected.get_values(), solution.get_values());
+    // The default strategies deduce more digits with the windows than without them.
+    let mut unboxed = grid.clone();
+    unboxed.constraints.windows = false;
+    let count_known = |grid: Grid| -> Result<usize, SolverError> {
+        let deduced = Solver::new(grid, SolverConfig::deduction_only()).deduce()?;
+        let known = deduced
+            .digits
+            .iter()
+            .filter(|digit| matches!(digit, Digit::Known(_)));
+        Ok(known.count())
+    };
+    assert!(count_known(grid.clone())? > count_known(unboxed)?);
+    // The windows rule out their digits in their own empty cells, also where no row, column or
+    // subgrid does.
+    let config = SolverConfig {
+        strategies: vec![],
+        ..SolverConfig::deduction_only()
+    };
+    let deduced = Solver::new(grid.clone(), config).deduce()?;
+    let mut unboxed = deduced.clone();
+    unboxed.constraints.windows = false;
+    let units = deduced.get_units();
+    let windows = units
+        .iter()
+        .filter(|unit| unit.kind == UnitKind::Window)
+        .collect::<Vec<_>>();
+    assert_eq!(4, windows.len());
+    let mut window_eliminations = 0;
+    for window in windows {
+        let digits = window
+            .cells
+            .iter()
+            .map(|cell| deduced.get_cell(*cell))
+            .collect::<Vec<_>>();
+        for cell in &window.cells {
+            if let Digit::Unknown(digit) = deduced.get_cell(*cell) {
+                let neighbours = unboxed.get_neighbour_digits(cell.column(), cell.row());
+                for value in 1..=SUBGRID_LENGTH as u32 {
+                    let known = Digit::Known(value);
+                    if digits.contains(&&known) {
+                        assert!(!digit.possible_values.contains(&value));
+                        if !neighbours.contains(&&known) {
+                            window_eliminations += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    assert!(window_eliminations > 0);
+    let mut classic = grid;
+    classic.constraints.windows = false;
+    let solver = Engine::DancingLinks.create(SolverConfig::default());
+    assert_eq!(2, solver.count_solutions(classic, 2)?);
+    Ok(())
+}
+
+#[test]
+fn test_killer_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/killer.grid").to_owned())?;
+    assert_eq!(35, grid.constraints.cages.len());
+    let expected = Parser::new().parse_line(
+        "254367198376189425189542673567491382491238567823756914715924836642813759938675241"
+            .to_owned(),
+    )?;
+    let solution = assert_unique_solution_by_all_engines(&grid)?;
+    assert_eq!(expected.get_values(), solution.get_values());
+    Ok(())
+}
+
+#[test]
+fn test_jigsaw_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/jigsaw.grid").to_owned())?;
+    assert!(!grid.constraints.has_subgrid_units());
+    let solution = assert_unique_solution_by_all_engines(&grid)?;
+    for region in 0..GRID_JOINT_SIZE {
+        let mut values = Vec::new();
+        for y in 0..GRID_JOINT_SIZE {
+            for x in 0..GRID_JOINT_SIZE {
+                if grid.constraints.get_region(x, y) == region {
+                    values.push(solution.get_digit(x, y));
+                }
+            }
+        }
+        values.sort();
+        values.dedup();
+        assert_eq!(GRID_JOINT_SIZE, values.len());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_anti_knight_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/anti_knight.grid").to_owned())?;
+    assert_eq!(vec![Relation::AntiKnight], grid.constraints.relations);
+    assert_unique_solution_by_all_engines(&grid)?;
+    let mut classic = grid;
+    classic.constraints.relations.clear();
+    let solver = Engine::DancingLinks.create(SolverConfig::default());
+    assert_eq!(2, solver.count_solutions(classic, 2)?);
+    Ok(())
+}
+
+#[test]
+fn test_kropki_grid_is_solved_by_all_engines() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/kropki.grid").to_owned())?;
+    assert!(grid.constraints.negative_dots);
+    let expected =
+        "678541923123789456459236178231957684786124395594368217342815769965472831817693542";
+    let solution = assert_unique_solution_by_all_engines(&grid)?;
+    let line = Generator::new().generate_line(&solution)?;
+    assert_eq!(expected, line.lines().next().unwrap());
+    Ok(())
+}
+
+#[test]
+fn test_cell_constraint_grids_are_solved_by_all_engines() -> TestResult {
+    let grids = [
+        (
+            include_str!("grids/thermometer.grid"),
+            "678541923123789456459236178231957684786124395594368217342815769965472831817693542",
+        ),
+        (
+            include_str!("grids/arrow.grid"),
+            "475683921123579468689124357231745689894236175567918234352891746716452893948367512",
+        ),
+        (
+            include_str!("grids/sandwich.grid"),
+            "267195834134678259589342167341826795756931482928754316493517628875263941612489573",
+        ),
+        (
+            include_str!("grids/parity.grid"),
+            "438591762571426893962873154195384276624715938387269541849652317253147689716938425",
+        ),
+    ];
+    for (syntax, expected) in &grids {
+        let grid = Parser::new().parse(syntax.to_string())?;
+        assert!(grid.constraints.get_cell_constraints().next().is_some());
+        let solution = assert_unique_solution_by_all_engines(&grid)?;
+        let line = Generator::new().generate_line(&solution)?;
+        assert_eq!(*expected, line.lines().next().unwrap());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parity_and_inequality_markers_are_needed_for_uniqueness() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/parity.grid").to_owned())?;
+    let (_, stats) = Solver::new(grid.clone(), SolverConfig::default()).solve_with_stats();
+    assert!(stats.eliminations(Strategy::CellConstraints) > 0);
+    let solver = Engine::DancingLinks.create(SolverConfig::default());
+    let mut without_inequalities = grid.clone();
+    without_inequalities.constraints.inequalities.clear();
+    assert_eq!(2, solver.count_solutions(without_inequalities, 2)?);
+    let mut without_parities = grid;
+    without_parities.constraints.parities.clear();
+    assert_eq!(2, solver.count_solutions(without_parities, 2)?);
+    Ok(())
+}
+
+#[test]
+fn test_engines_enumerate_multiple_solutions() -> TestResult {
+    for engine in &Engine::ALL {
+        let solver = engine.create(SolverConfig::default());
+        let solutions = solver.find_solutions(Grid::default(), 3)?;
+        assert_eq!(3, solutions.len(), "{}", solver.name());
+        assert!(solutions.iter().all(Grid::is_solved_correctly));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_engines_respect_node_limit() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/guessing.grid").to_owned())?;
+    let limits = SearchLimits {
+        max_nodes: Some(1),
+        ..SearchLimits::default()
+    };
+    for engine in &Engine::ALL {
+        let solver = engine.create(SolverConfig::default());
+        let expected = Err(SolverError::LimitExceeded(Limit::Nodes));
+        let actual = solver.solve_with_limits(grid.clone(), &limits);
+        assert_eq!(expected, actual, "{}", solver.name());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_dimacs_export_is_solved_end_to_end() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/hard.grid").to_owned())?;
+    let expected = Solver::new(grid.clone(), SolverConfig::default()).solve()?;
+    let dimacs = Cnf::from_grid(&grid).to_dimacs();
+    let cnf = Cnf::parse_dimacs(&dimacs)?;
+    let model = Dpll::new(&cnf)
+        .solve(&SearchLimits::default())?
+        .ok_or("Formula is unsatisfiable")?;
+    let output: Vec<String> = model.iter().map(i32::to_string).collect();
+    let model = parse_model(&format!("s SATISFIABLE\nv {} 0\n", output.join(" ")))?;
+    assert_eq!(expected, decode_model(&model)?);
+    Ok(())
+}
+
+#[test]
+fn test_samurai_is_solved_jointly() -> TestResult {
+    let samurai = Parser::new().parse_samurai(include_str!("grids/samurai.grid").to_owned())?;
+    // None of the grids has a unique solution on its own.
+    for grid in &samurai.grids {
+        let solutions = Solver::new(grid.clone(), SolverConfig::default()).find_solutions(2)?;
+        assert_eq!(2, solutions.len());
+    }
+    let mut solutions = SamuraiSolver::new(SolverConfig::default()).find_solutions(samurai, 2)?;
+    assert_eq!(1, solutions.len());
+    let solved_samurai = solutions.remove(0);
+    assert!(solved_samurai.is_solved_correctly());
+    let expected = [
+        "625874913   365719248",
+        "831269754   728364591",
+        "947153682   149852763",
+        "354918267   687591432",
+        "278346591   594236817",
+        "169527438   231478956",
+        "716485329145876145329",
+        "492631875296413927685",
+        "583792146837952683174",
+        "      238514697",
+        "      567983124",
+        "      491672385",
+        "945782613758249573861",
+        "261395784329561284397",
+        "738641952461738619245",
+        "427156839   694358712",
+        "316829475   852741639",
+        "589437261   173926584",
+        "194578326   317892456",
+        "673214598   426135978",
+        "852963147   985467123",
+    ];
+    let actual = Generator::new().generate_samurai(&solved_samurai)?;
+    assert_eq!(expected.join("\n"), actual.trim_end());
+    Ok(())
+}
+
+#[test]
+fn test_disguised_puzzle_is_equivalent_and_solved_alike() -> TestResult {
+    let grid = Parser::new().parse(include_str!("grids/hard.grid").to_owned())?;
+    let transformation = Transformation::reflect_vertically()
+        .then(&Transformation::swap_stacks(0, 2)?)
+        .then(&Transformation::swap_columns(1, 2)?)
+        .then(&Transformation::relabel(&[2, 3, 4, 5, 6, 7, 8, 9, 1])?);
+    let disguised = transformation.apply(&grid)?;
+    assert!(grid.is_equivalent(&disguised));
+    let other = Parser::new().parse(include_str!("grids/medium.grid").to_owned())?;
+    assert!(!other.is_equivalent(&disguised));
+    let solution = Solver::new(grid, SolverConfig::default()).solve()?;
+    let disguised_solution = Solver::new(disguised, SolverConfig::default()).solve()?;
+    assert_eq!(
+        solution,
+        transformation.inverse().apply(&disguised_solution)?
+    );
+    Ok(())
+}
+
 fn test_grid_is_solved_correctly(board_syntax: &'static str) -> TestResult {
     let grid_syntax = board_syntax.to_owned();
     let parser = Parser::default();
     let grid = parser.parse(grid_syntax)?;
-    let solver = Solver::new(grid);
+    let solver = Solver::new(grid, SolverConfig::default());
     let solved_grid = solver.solve()?;
     assert!(solved_grid.is_solved_correctly());
     Ok(())